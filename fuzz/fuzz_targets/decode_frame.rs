@@ -0,0 +1,22 @@
+#![no_main]
+
+use aether_protocol::framing::read_frame_with;
+use aether_protocol::{ByteSize, ProtocolConfig, Request};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // `read_frame_with` is the first thing to see bytes from an untrusted
+    // peer. Small limits keep oversized length prefixes and decompression
+    // bombs from hiding behind the allocator.
+    let config = ProtocolConfig {
+        max_frame_size: ByteSize::from_kib(64),
+        max_message_size: ByteSize::from_kib(256),
+        ..Default::default()
+    };
+    let mut reader = data;
+    while !reader.is_empty() {
+        if read_frame_with::<_, Request>(&mut reader, &config).is_err() {
+            break;
+        }
+    }
+});
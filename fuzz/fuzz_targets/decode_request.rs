@@ -0,0 +1,15 @@
+#![no_main]
+
+use aether_protocol::Request;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // bincode is the wire format, but JSON is used wherever records carry
+    // `serde_json::Value`, so both decoders see every input.
+    if let Ok(value) = bincode::deserialize::<Request>(data) {
+        bincode::serialize(&value).expect("decoded value must re-encode");
+    }
+    if let Ok(value) = serde_json::from_slice::<Request>(data) {
+        serde_json::to_vec(&value).expect("decoded value must re-encode");
+    }
+});
@@ -0,0 +1,37 @@
+#![no_main]
+
+use aether_protocol::{compare_values, Filter};
+use libfuzzer_sys::fuzz_target;
+use std::cmp::Ordering;
+
+fuzz_target!(|data: &[u8]| {
+    // bincode is the wire format, but JSON is used wherever records carry
+    // `serde_json::Value`, so both decoders see every input. The query-string
+    // parser takes human input, so it must reject garbage without panicking.
+    let mut filters = Vec::new();
+    if let Ok(value) = bincode::deserialize::<Filter>(data) {
+        bincode::serialize(&value).expect("decoded value must re-encode");
+        filters.push(value);
+    }
+    if let Ok(value) = serde_json::from_slice::<Filter>(data) {
+        serde_json::to_vec(&value).expect("decoded value must re-encode");
+        filters.push(value);
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        filters.extend(Filter::parse(text));
+    }
+
+    // Drive the code that decides what a filter matches: normalization, the
+    // query-string rendering, and the value ordering behind range filters.
+    for filter in &filters {
+        let _ = filter.normalize();
+        let _ = Filter::parse(&filter.to_query_string());
+        if let Filter::Equals { value, .. }
+        | Filter::NotEquals { value, .. }
+        | Filter::GreaterThan { value, .. }
+        | Filter::LessThan { value, .. } = filter
+        {
+            assert_eq!(compare_values(value, value), Ordering::Equal);
+        }
+    }
+});
@@ -0,0 +1,340 @@
+// File: src/codec.rs
+// =============================================================================
+// This file provides the encode/decode entry points for protocol messages.
+// Messages are bincode on the wire unless the codec is given another
+// `WireFormat`. A `Codec` can carry an `Instrument`, which
+// is told about every message that passes through so servers and clients can
+// export per-message-type metrics without wrapping the codec themselves, and
+// `CodecHooks`, which see the messages themselves and any failures for
+// middleware such as auditing or schema-drift detection. A codec can also be
+// told to decode messages from newer peers that use variants it does not
+// know as `Request::Unknown`/`Response::Unknown`, so fleets can be upgraded
+// one node at a time.
+
+use crate::error::ProtocolError;
+use crate::format::WireFormat;
+use crate::request::RequestEnvelope;
+use crate::response::ResponseEnvelope;
+use crate::{Request, Response};
+use serde::de::{self, DeserializeOwned, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Whether a message was being encoded or decoded when it was observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrafficDirection {
+    Encode,
+    Decode,
+}
+
+/// Receives one call per message successfully encoded or decoded by a `Codec`.
+pub trait Instrument: Send + Sync {
+    /// `variant` is the message's variant name (e.g. `"FindRecords"`) and
+    /// `encoded_size` the size of its wire form in bytes.
+    fn record(&self, direction: TrafficDirection, variant: &'static str, encoded_size: usize, elapsed: Duration);
+}
+
+/// A message passing through a `Codec`, as shown to `CodecHooks`. Envelopes
+/// are shown as their body.
+#[derive(Debug, Clone, Copy)]
+pub enum Message<'a> {
+    Request(&'a Request),
+    Response(&'a Response),
+}
+
+impl Message<'_> {
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Message::Request(request) => request.variant_name(),
+            Message::Response(response) => response.variant_name(),
+        }
+    }
+}
+
+/// Callbacks a `Codec` invokes as messages are encoded and decoded. Every
+/// method does nothing by default, so implementors override only the events
+/// they care about.
+pub trait CodecHooks: Send + Sync {
+    /// Called after `message` was encoded to `bytes`.
+    fn on_message_encoded(&self, _message: Message<'_>, _bytes: &[u8]) {}
+
+    /// Called after `bytes` were decoded to `message`.
+    fn on_message_decoded(&self, _message: Message<'_>, _bytes: &[u8]) {}
+
+    /// Called when encoding or decoding fails. `bytes` is the undecodable
+    /// input, or empty when encoding failed.
+    fn on_frame_error(&self, _direction: TrafficDirection, _error: &ProtocolError, _bytes: &[u8]) {}
+}
+
+/// Encodes and decodes protocol messages, optionally reporting to an
+/// `Instrument` and `CodecHooks`.
+#[derive(Clone, Default)]
+pub struct Codec {
+    instrument: Option<Arc<dyn Instrument>>,
+    hooks: Option<Arc<dyn CodecHooks>>,
+    unknown_variants: bool,
+    format: WireFormat,
+}
+
+impl Codec {
+    /// Creates a codec without instrumentation.
+    pub fn new() -> Self {
+        Codec::default()
+    }
+
+    /// Creates a codec that reports every message to `instrument`.
+    pub fn with_instrument(instrument: Arc<dyn Instrument>) -> Self {
+        Codec { instrument: Some(instrument), ..Codec::default() }
+    }
+
+    /// Invokes `hooks` for every message and failure, in addition to any
+    /// instrument.
+    pub fn with_hooks(mut self, hooks: Arc<dyn CodecHooks>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Encodes and decodes messages in `format` instead of bincode, once
+    /// the peer has agreed to it in the handshake.
+    pub fn with_format(mut self, format: WireFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// When enabled, a message that fails to decode only because it contains
+    /// an enum variant this build does not know, at any depth, decodes as
+    /// `Request::Unknown` or `Response::Unknown`. Envelopes keep their
+    /// `request_id`, so the peer can still be answered. Disabled by default.
+    pub fn with_unknown_variants(mut self, enabled: bool) -> Self {
+        self.unknown_variants = enabled;
+        self
+    }
+
+    pub fn encode_request(&self, request: &Request) -> Result<Vec<u8>, ProtocolError> {
+        self.encode(request, Message::Request(request))
+    }
+
+    pub fn decode_request(&self, bytes: &[u8]) -> Result<Request, ProtocolError> {
+        self.decode(bytes, |request: &Request| Message::Request(request))
+    }
+
+    pub fn encode_response(&self, response: &Response) -> Result<Vec<u8>, ProtocolError> {
+        self.encode(response, Message::Response(response))
+    }
+
+    pub fn decode_response(&self, bytes: &[u8]) -> Result<Response, ProtocolError> {
+        self.decode(bytes, |response: &Response| Message::Response(response))
+    }
+
+    /// Envelopes are reported under the variant name of their body.
+    pub fn encode_request_envelope(&self, envelope: &RequestEnvelope) -> Result<Vec<u8>, ProtocolError> {
+        self.encode(envelope, Message::Request(&envelope.body))
+    }
+
+    pub fn decode_request_envelope(&self, bytes: &[u8]) -> Result<RequestEnvelope, ProtocolError> {
+        self.decode(bytes, |envelope: &RequestEnvelope| Message::Request(&envelope.body))
+    }
+
+    pub fn encode_response_envelope(&self, envelope: &ResponseEnvelope) -> Result<Vec<u8>, ProtocolError> {
+        self.encode(envelope, Message::Response(&envelope.body))
+    }
+
+    pub fn decode_response_envelope(&self, bytes: &[u8]) -> Result<ResponseEnvelope, ProtocolError> {
+        self.decode(bytes, |envelope: &ResponseEnvelope| Message::Response(&envelope.body))
+    }
+
+    fn encode<T: Serialize>(&self, message: &T, observed: Message<'_>) -> Result<Vec<u8>, ProtocolError> {
+        let start = Instant::now();
+        let bytes = match self.format.encode(message) {
+            Ok(bytes) => bytes,
+            Err(e) => return Err(self.report_error(TrafficDirection::Encode, e, &[])),
+        };
+        if let Some(instrument) = &self.instrument {
+            instrument.record(TrafficDirection::Encode, observed.variant_name(), bytes.len(), start.elapsed());
+        }
+        if let Some(hooks) = &self.hooks {
+            hooks.on_message_encoded(observed, &bytes);
+        }
+        Ok(bytes)
+    }
+
+    fn decode<T: DeserializeOwned + Unknown>(&self, bytes: &[u8], observe: fn(&T) -> Message<'_>) -> Result<T, ProtocolError> {
+        let start = Instant::now();
+        let message = match self.format.decode(bytes) {
+            Ok(message) => message,
+            Err(e) => match self.unknown_variants.then(|| T::unknown(self.format, bytes)).flatten() {
+                Some(message) => message,
+                None => return Err(self.report_error(TrafficDirection::Decode, e, bytes)),
+            },
+        };
+        if let Some(instrument) = &self.instrument {
+            instrument.record(TrafficDirection::Decode, observe(&message).variant_name(), bytes.len(), start.elapsed());
+        }
+        if let Some(hooks) = &self.hooks {
+            hooks.on_message_decoded(observe(&message), bytes);
+        }
+        Ok(message)
+    }
+
+    fn report_error(&self, direction: TrafficDirection, error: ProtocolError, bytes: &[u8]) -> ProtocolError {
+        if let Some(hooks) = &self.hooks {
+            hooks.on_frame_error(direction, &error, bytes);
+        }
+        error
+    }
+}
+
+/// Builds the stand-in for a message that uses a variant this build does not
+/// know. `bytes` is the undecodable message, in `format`; `None` means its
+/// variant is known and it failed to decode for some other reason.
+trait Unknown: Sized {
+    fn unknown(format: WireFormat, bytes: &[u8]) -> Option<Self>;
+}
+
+impl Unknown for Request {
+    fn unknown(format: WireFormat, bytes: &[u8]) -> Option<Self> {
+        is_unknown_variant::<Request>(outer_variant(format, bytes)?).then_some(Request::Unknown)
+    }
+}
+
+impl Unknown for Response {
+    fn unknown(format: WireFormat, bytes: &[u8]) -> Option<Self> {
+        is_unknown_variant::<Response>(outer_variant(format, bytes)?).then_some(Response::Unknown)
+    }
+}
+
+/// Just the `request_id` of an envelope. It comes first, so it decodes even
+/// when the body does not: bincode stops after it, and the self-describing
+/// formats skip the body.
+#[derive(Deserialize)]
+struct EnvelopeId {
+    request_id: u64,
+}
+
+/// Just the variant name of an envelope's body, for the self-describing
+/// formats.
+#[derive(Deserialize)]
+struct EnvelopeVariant {
+    body: VariantName,
+}
+
+impl Unknown for RequestEnvelope {
+    fn unknown(format: WireFormat, bytes: &[u8]) -> Option<Self> {
+        let EnvelopeId { request_id } = format.decode(bytes).ok()?;
+        is_unknown_variant::<Request>(envelope_variant(format, bytes)?)
+            .then_some(RequestEnvelope::new(request_id, Request::Unknown))
+    }
+}
+
+impl Unknown for ResponseEnvelope {
+    fn unknown(format: WireFormat, bytes: &[u8]) -> Option<Self> {
+        let EnvelopeId { request_id } = format.decode(bytes).ok()?;
+        is_unknown_variant::<Response>(envelope_variant(format, bytes)?)
+            .then_some(ResponseEnvelope { request_id, body: Response::Unknown })
+    }
+}
+
+/// The outer variant of an encoded message: its index in bincode, its name
+/// in the self-describing formats.
+enum Variant {
+    Index(u32),
+    Name(String),
+}
+
+fn outer_variant(format: WireFormat, bytes: &[u8]) -> Option<Variant> {
+    match format {
+        WireFormat::Bincode => bincode::deserialize(bytes).ok().map(Variant::Index),
+        _ => format.decode(bytes).ok().map(|VariantName(name)| Variant::Name(name)),
+    }
+}
+
+/// The outer variant of an encoded envelope's body. In bincode the body
+/// follows the 8-byte `request_id`.
+fn envelope_variant(format: WireFormat, bytes: &[u8]) -> Option<Variant> {
+    match format {
+        WireFormat::Bincode => outer_variant(format, bytes.get(8..)?),
+        _ => format.decode(bytes).ok().map(|EnvelopeVariant { body: VariantName(name) }| Variant::Name(name)),
+    }
+}
+
+/// Whether `variant` is missing from `T`. Only the outer variant is checked,
+/// so a known variant with a bad field, even a bad nested enum, stays an
+/// error rather than becoming `Unknown`.
+fn is_unknown_variant<T: DeserializeOwned>(variant: Variant) -> bool {
+    let known = variant_names::<T>();
+    match variant {
+        Variant::Index(index) => index as usize >= known.len(),
+        Variant::Name(name) => !known.contains(&name.as_str()),
+    }
+}
+
+/// The variants `T` accepts, in declaration order, as its derived
+/// `Deserialize` lists them. Skipped variants such as `Unknown` are left out,
+/// and bincode numbers the rest from zero.
+fn variant_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut names: &'static [&'static str] = &[];
+    let _ = T::deserialize(VariantProbe(&mut names));
+    names
+}
+
+/// A deserializer that only records the variant list it is asked for.
+struct VariantProbe<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for VariantProbe<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not an enum"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = variants;
+        Err(de::Error::custom("variant names recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// The name of an externally tagged variant: a bare string for unit
+/// variants, the only key of a map for the rest.
+struct VariantName(String);
+
+impl<'de> Deserialize<'de> for VariantName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(VariantNameVisitor)
+    }
+}
+
+struct VariantNameVisitor;
+
+impl<'de> Visitor<'de> for VariantNameVisitor {
+    type Value = VariantName;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a variant name or a map with one entry")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<VariantName, E> {
+        Ok(VariantName(name.to_string()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<VariantName, A::Error> {
+        let Some((name, IgnoredAny)) = map.next_entry::<String, IgnoredAny>()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+        if map.next_key::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(VariantName(name))
+    }
+}
@@ -0,0 +1,62 @@
+// File: src/config.rs
+// =============================================================================
+// This file defines `ProtocolConfig`, the limits that codecs, request
+// validators and servers enforce. Keeping them in one serializable struct
+// lets a deployment load them from its own config file and hand the same
+// values to every layer, so a message accepted by one layer is not rejected
+// by the next.
+
+use crate::error::ProtocolError;
+use crate::types::{ByteSize, Filter};
+use serde::{Deserialize, Serialize};
+
+/// Limits shared by every layer that handles protocol messages. Missing
+/// fields take their default when deserialized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ProtocolConfig {
+    /// The largest frame payload a decoder accepts. Larger messages are sent
+    /// as several continuation frames.
+    pub max_frame_size: ByteSize,
+    /// The largest message a decoder accepts, summed over its continuation
+    /// frames and measured after decompression.
+    pub max_message_size: ByteSize,
+    /// The most operations or keys one batch request may carry.
+    pub max_batch_size: usize,
+    /// How deeply `And`, `Or` and `Not` may nest in a filter.
+    pub max_filter_depth: usize,
+    /// The page size servers use when a paginated request does not set one.
+    pub default_page_size: usize,
+}
+
+impl Default for ProtocolConfig {
+    /// Conservative limits suitable for a server exposed to untrusted clients.
+    fn default() -> Self {
+        ProtocolConfig {
+            max_frame_size: ByteSize::from_mib(16),
+            max_message_size: ByteSize::from_mib(256),
+            max_batch_size: 1_000,
+            max_filter_depth: 128,
+            default_page_size: 100,
+        }
+    }
+}
+
+impl ProtocolConfig {
+    /// Rejects a filter nested deeper than `max_filter_depth`.
+    pub fn check_filter(&self, filter: &Filter) -> Result<(), ProtocolError> {
+        check_limit("max_filter_depth", filter.depth(), self.max_filter_depth)
+    }
+
+    /// Rejects a batch of `len` items larger than `max_batch_size`.
+    pub fn check_batch_len(&self, len: usize) -> Result<(), ProtocolError> {
+        check_limit("max_batch_size", len, self.max_batch_size)
+    }
+}
+
+fn check_limit(limit: &'static str, value: usize, max: usize) -> Result<(), ProtocolError> {
+    if value > max {
+        return Err(ProtocolError::LimitExceeded { limit, value, max });
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+// File: src/corpus.rs
+// =============================================================================
+// This file builds the seed corpus for the fuzz targets in `fuzz/`. The seeds
+// are ordinary protocol messages picked to sit on awkward boundaries (empty
+// strings, extreme numbers, deep nesting) so the fuzzer starts from inputs
+// that already reach the edge cases of the decoders.
+
+use crate::framing::{write_frame_with, FrameOptions};
+use crate::types::{BatchRequest, ByteSize, Direction, DurationMs, Filter, Page, PageInfo, Projection, ProjectionEntry, QueryOptions, Record, RecordSet};
+use crate::{Request, Response};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How deep the nested `And`/`Or` seed filters go. Each level costs two JSON
+/// nesting levels, so this stays inside serde_json's default recursion limit.
+const NESTING_DEPTH: usize = 32;
+
+/// Returns requests built from boundary values.
+pub fn requests() -> Vec<Request> {
+    vec![
+        Request::CreateDatabase { db_name: String::new() },
+        Request::DropDatabase { db_name: "\u{0}\u{ffff}db".to_string() },
+        Request::ListDatabases { options: Default::default() },
+        Request::GetRecord {
+            db_name: Some("db".to_string()),
+            collection: "c".repeat(1024),
+            record_id: "🦀".to_string(),
+            with_version: false,
+        },
+        Request::CreateRecord {
+            db_name: Some("db".to_string()),
+            collection: "users".to_string(),
+            record_id: String::new(),
+            data: boundary_record(),
+            ttl: Some(DurationMs(u64::MAX)),
+        },
+        Request::FindRecords {
+            db_name: Some("db".to_string()),
+            collection: "users".to_string(),
+            filter: nested_filter(NESTING_DEPTH),
+            options: Some(QueryOptions {
+                sort_by: Some(("".into(), Direction::Asc)),
+                limit: Some(usize::MAX),
+                offset: Some(usize::MAX),
+                max_response_bytes: Some(ByteSize(0)),
+                ..Default::default()
+            }),
+        },
+        Request::CountRecords {
+            db_name: Some("db".to_string()),
+            collection: "users".to_string(),
+            filter: Filter::In { field: "f".into(), values: Vec::new(), options: Default::default() },
+        },
+        Request::ExecuteBatchGet(BatchRequest {
+            requests: HashMap::from([(String::new(), (String::new(), String::new(), String::new()))]),
+            projections: HashMap::from([(String::new(), Projection { fields: vec![ProjectionEntry::Field("".into())] })]),
+        }),
+        Request::Search {
+            db_name: Some("db".to_string()),
+            collection: "users".to_string(),
+            query: String::new(),
+            field: None,
+        },
+    ]
+}
+
+/// Returns responses built from boundary values.
+pub fn responses() -> Vec<Response> {
+    vec![
+        Response::Success,
+        Response::Error(String::new()),
+        Response::DatabaseList(Page {
+            items: vec![String::new(); 16],
+            page_info: PageInfo { next_page_token: Some(String::new()), total_count: Some(u64::MAX) },
+        }),
+        Response::Record(Some(boundary_record())),
+        Response::Record(None),
+        Response::RecordSet(RecordSet { records: vec![Record::new(), boundary_record()], ..Default::default() }),
+        Response::RecordCount(u64::MAX),
+        Response::LastInsertId(0),
+        Response::RecordWithRelated(Some((Record::new(), Record::new()))),
+        Response::ResultMetrics {
+            data: Box::new(Response::ResultMetrics {
+                data: Box::new(Response::Success),
+                metrics: crate::QueryMetrics { execution_time_micros: u64::MAX, used_top_k: true },
+            }),
+            metrics: crate::QueryMetrics { execution_time_micros: 0, ..Default::default() },
+        },
+    ]
+}
+
+/// Returns filters built from boundary values, including deeply nested trees.
+pub fn filters() -> Vec<Filter> {
+    vec![
+        Filter::Equals { field: "a.b[0].c[18446744073709551615]".into(), value: json!(null), options: Default::default() },
+        Filter::NotEquals { field: "f".into(), value: json!({ "nested": [[], {}] }) },
+        Filter::GreaterThan { field: "f".into(), value: json!(f64::MAX) },
+        Filter::LessThan { field: "f".into(), value: json!(f64::MIN_POSITIVE) },
+        Filter::GreaterThan { field: "created_at".into(), value: json!("2024-01-01T00:00:00Z") },
+        Filter::And(Vec::new()),
+        Filter::Or(Vec::new()),
+        nested_filter(NESTING_DEPTH),
+    ]
+}
+
+/// Writes the seeds into `root/<target>/` for each fuzz target, in both the
+/// bincode and JSON encodings, and returns the number of files written.
+/// `decode_frame` gets the requests as frames, plain and with checksummed
+/// continuation frames.
+pub fn write_corpus<P: AsRef<Path>>(root: P) -> io::Result<usize> {
+    let root = root.as_ref();
+    let mut written = write_target(root, "decode_request", &requests())?;
+    written += write_target(root, "decode_response", &responses())?;
+    written += write_target(root, "filter_matches", &filters())?;
+    written += write_frames(root, "decode_frame", &requests())?;
+    Ok(written)
+}
+
+fn write_frames<T: Serialize>(root: &Path, target: &str, seeds: &[T]) -> io::Result<usize> {
+    let dir = root.join(target);
+    fs::create_dir_all(&dir)?;
+
+    let split = FrameOptions { checksum: true, max_frame_len: 16, ..Default::default() };
+    let mut written = 0;
+    for (i, seed) in seeds.iter().enumerate() {
+        for (name, options) in [("plain", FrameOptions::default()), ("split", split)] {
+            let mut bytes = Vec::new();
+            if write_frame_with(&mut bytes, seed, options).is_ok() {
+                fs::write(dir.join(format!("seed-{i:03}-{name}.bin")), bytes)?;
+                written += 1;
+            }
+        }
+    }
+    Ok(written)
+}
+
+fn write_target<T: Serialize>(root: &Path, target: &str, seeds: &[T]) -> io::Result<usize> {
+    let dir = root.join(target);
+    fs::create_dir_all(&dir)?;
+
+    let mut written = 0;
+    for (i, seed) in seeds.iter().enumerate() {
+        if let Ok(bytes) = bincode::serialize(seed) {
+            fs::write(dir.join(format!("seed-{i:03}.bin")), bytes)?;
+            written += 1;
+        }
+        if let Ok(bytes) = serde_json::to_vec(seed) {
+            fs::write(dir.join(format!("seed-{i:03}.json")), bytes)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+fn boundary_record() -> Record {
+    let mut record = Record::new();
+    record.insert(String::new(), json!(null));
+    record.insert("max".to_string(), json!(u64::MAX));
+    record.insert("min".to_string(), json!(i64::MIN));
+    record.insert("float".to_string(), json!(f64::EPSILON));
+    record.insert("text".to_string(), json!("\u{0}\"\\\u{10ffff}"));
+    record.insert("array".to_string(), json!([[[]], {}, null]));
+    record
+}
+
+fn nested_filter(depth: usize) -> Filter {
+    let leaf = Filter::Equals { field: "leaf".into(), value: json!(true), options: Default::default() };
+    (0..depth).fold(leaf, |inner, i| {
+        if i % 2 == 0 {
+            Filter::And(vec![inner])
+        } else {
+            Filter::Or(vec![inner])
+        }
+    })
+}
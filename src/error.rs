@@ -0,0 +1,107 @@
+// File: src/error.rs
+// =============================================================================
+// This file defines the error type returned by the fallible helpers in this
+// crate. It covers building requests (unbound params, filter and field path
+// parsing), encoding and decoding in each wire format, reading and writing
+// frames (magic, version, compression, checksums, size and nesting limits) and
+// typed access to record fields (missing fields, type mismatches).
+
+use std::fmt;
+use std::io;
+
+/// Errors produced by the protocol helpers in this crate.
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// A `Filter::Param` placeholder had no value supplied when binding.
+    UnboundParam(String),
+    /// A field path was malformed, e.g. `"a..b"` or `"items[x]"`.
+    InvalidFieldPath(String),
+    /// A filter query string could not be parsed; `position` is the byte
+    /// offset where parsing stopped.
+    InvalidFilter { position: usize, message: String },
+    /// Reading or writing the underlying stream failed.
+    Io(io::Error),
+    /// A message could not be encoded or decoded as JSON.
+    Json(serde_json::Error),
+    /// A message could not be encoded or decoded as bincode.
+    Bincode(bincode::Error),
+    /// A message could not be encoded or decoded as MessagePack or CBOR.
+    Encoding(Box<dyn std::error::Error + Send + Sync>),
+    /// The input did not start with the expected magic bytes.
+    BadMagic,
+    /// The input uses a format version this crate does not understand.
+    UnsupportedVersion(u8),
+    /// A frame announced a payload larger than the reader accepts.
+    FrameTooLarge { len: usize, max: usize },
+    /// A frame was compressed with an algorithm this build does not support.
+    UnsupportedCompression(u8),
+    /// A message uses a `WireFormat` this build does not support.
+    UnsupportedFormat(u8),
+    /// A message exceeded one of the limits in `ProtocolConfig`; `limit` is
+    /// the name of the config field.
+    LimitExceeded { limit: &'static str, value: usize, max: usize },
+    /// A frame's checksum trailer did not match its contents, so it was
+    /// corrupted or truncated in transit.
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// A record had no value at the given field path.
+    MissingField(String),
+    /// A record's value at `path` was not of the `expected` type.
+    FieldType { path: String, expected: &'static str, found: &'static str },
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::UnboundParam(name) => write!(f, "no value bound for filter parameter '{name}'"),
+            ProtocolError::InvalidFieldPath(path) => write!(f, "invalid field path '{path}'"),
+            ProtocolError::InvalidFilter { position, message } => write!(f, "invalid filter at byte {position}: {message}"),
+            ProtocolError::Io(e) => write!(f, "i/o error: {e}"),
+            ProtocolError::Json(e) => write!(f, "json error: {e}"),
+            ProtocolError::Bincode(e) => write!(f, "bincode error: {e}"),
+            ProtocolError::Encoding(e) => write!(f, "encoding error: {e}"),
+            ProtocolError::BadMagic => write!(f, "input does not start with the expected magic bytes"),
+            ProtocolError::UnsupportedVersion(version) => write!(f, "unsupported format version {version}"),
+            ProtocolError::FrameTooLarge { len, max } => write!(f, "frame of {len} bytes exceeds the {max}-byte limit"),
+            ProtocolError::UnsupportedCompression(id) => write!(f, "unsupported frame compression {id}"),
+            ProtocolError::UnsupportedFormat(id) => write!(f, "unsupported wire format {id}"),
+            ProtocolError::LimitExceeded { limit, value, max } => write!(f, "{value} exceeds {limit} of {max}"),
+            ProtocolError::ChecksumMismatch { expected, actual } => {
+                write!(f, "frame checksum mismatch: expected {expected:#010x}, computed {actual:#010x}")
+            }
+            ProtocolError::MissingField(path) => write!(f, "record has no field '{path}'"),
+            ProtocolError::FieldType { path, expected, found } => {
+                write!(f, "field '{path}' is {found}, expected {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProtocolError::Io(e) => Some(e),
+            ProtocolError::Json(e) => Some(e),
+            ProtocolError::Bincode(e) => Some(e),
+            ProtocolError::Encoding(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ProtocolError {
+    fn from(e: io::Error) -> Self {
+        ProtocolError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ProtocolError {
+    fn from(e: serde_json::Error) -> Self {
+        ProtocolError::Json(e)
+    }
+}
+
+impl From<bincode::Error> for ProtocolError {
+    fn from(e: bincode::Error) -> Self {
+        ProtocolError::Bincode(e)
+    }
+}
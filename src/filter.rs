@@ -0,0 +1,965 @@
+// File: src/filter.rs
+// =============================================================================
+// This file holds the logic that operates on `Filter` trees. The `Filter` type
+// itself lives in `types.rs` with the rest of the shared data structures; this
+// module only adds behaviour on top of it.
+
+use crate::error::ProtocolError;
+use crate::types::{FieldPath, Filter, GeoPoint, GeoRegion, StringCompareOptions, TextSearchOptions, ValueType};
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Not;
+use std::str::FromStr;
+
+/// Words with a meaning in the filter syntax. Field names that spell one of
+/// these (in any case) are quoted with backticks.
+const KEYWORDS: &[&str] = &[
+    "ALL", "AND", "ANY", "BETWEEN", "BOX", "CONTAINS", "ENDS", "EXCLUSIVE", "EXISTS", "IN", "IS", "MATCHES", "NEAR", "NOT",
+    "OR", "POINT", "POLYGON", "SEARCH", "STARTS", "WITH", "WITHIN",
+];
+
+/// How deeply `Filter::parse` lets groups and negations nest, so hostile input
+/// cannot exhaust the stack. Matches `ProtocolConfig::default().max_filter_depth`.
+const MAX_PARSE_DEPTH: usize = 128;
+
+/// Replaces every `Filter::Param` placeholder in `filter` with the filter bound
+/// to its name in `values`, returning the filled-in copy.
+///
+/// Bound filters are inserted as-is and are not searched for further
+/// placeholders, so a binding can never expand into itself.
+pub fn bind_params(filter: &Filter, values: &HashMap<String, Filter>) -> Result<Filter, ProtocolError> {
+    match filter {
+        Filter::Param(name) => values.get(name).cloned().ok_or_else(|| ProtocolError::UnboundParam(name.clone())),
+        Filter::And(filters) => Ok(Filter::And(bind_all(filters, values)?)),
+        Filter::Or(filters) => Ok(Filter::Or(bind_all(filters, values)?)),
+        Filter::Not(inner) => Ok(Filter::Not(Box::new(bind_params(inner, values)?))),
+        other => Ok(other.clone()),
+    }
+}
+
+fn bind_all(filters: &[Filter], values: &HashMap<String, Filter>) -> Result<Vec<Filter>, ProtocolError> {
+    filters.iter().map(|filter| bind_params(filter, values)).collect()
+}
+
+/// Orders two JSON values the way range filters and sorts compare them.
+///
+/// Values of different types order by type: null < bool < number < string <
+/// array < object. Within a type, numbers compare numerically (exactly for
+/// integers), strings by code point, so ISO-8601 timestamps order
+/// chronologically, and arrays element by element. Objects compare their
+/// entries in key order.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => compare_numbers(a, b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => compare_sequences(a.iter(), b.iter()),
+        (Value::Object(a), Value::Object(b)) => {
+            let mut a: Vec<_> = a.iter().collect();
+            let mut b: Vec<_> = b.iter().collect();
+            a.sort_by(|x, y| x.0.cmp(y.0));
+            b.sort_by(|x, y| x.0.cmp(y.0));
+            for ((a_key, a_value), (b_key, b_value)) in a.iter().zip(&b) {
+                let ordering = a_key.cmp(b_key).then_with(|| compare_values(a_value, b_value));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        _ => ValueType::of(a).cmp(&ValueType::of(b)),
+    }
+}
+
+fn compare_numbers(a: &Number, b: &Number) -> Ordering {
+    if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+        return a.cmp(&b);
+    }
+    if let (Some(a), Some(b)) = (a.as_u64(), b.as_u64()) {
+        return a.cmp(&b);
+    }
+    let a = a.as_f64().unwrap_or(f64::NAN);
+    let b = b.as_f64().unwrap_or(f64::NAN);
+    a.total_cmp(&b)
+}
+
+fn compare_sequences<'a>(mut a: impl Iterator<Item = &'a Value>, mut b: impl Iterator<Item = &'a Value>) -> Ordering {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => match compare_values(x, y) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            },
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+        }
+    }
+}
+
+impl Filter {
+    /// Renders the filter in the compact query syntax; see the `Display` impl.
+    pub fn to_query_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses a filter written in the query syntax, e.g.
+    /// `age > 21 AND status = "active"`. Keywords are case-insensitive, and
+    /// `OR` binds looser than `AND`, which binds looser than `NOT`.
+    ///
+    /// Parsing the text written by the `Display` impl gives back the same
+    /// filter tree.
+    pub fn parse(input: &str) -> Result<Filter, ProtocolError> {
+        let mut parser = Parser { input, pos: 0, depth: 0 };
+        let filter = parser.parse_or()?;
+        parser.skip_whitespace();
+        if parser.pos < input.len() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+        Ok(filter)
+    }
+
+    /// How many levels of `And`, `Or` and `Not` the filter nests, counting a
+    /// lone predicate as 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            Filter::And(filters) | Filter::Or(filters) => 1 + filters.iter().map(Filter::depth).max().unwrap_or(0),
+            Filter::Not(inner) => 1 + inner.depth(),
+            _ => 1,
+        }
+    }
+
+    /// Starts a predicate on `field`, e.g. `Filter::field("age").gt(21)`.
+    pub fn field(field: impl Into<FieldPath>) -> FilterBuilder {
+        FilterBuilder { field: field.into(), options: StringCompareOptions::default() }
+    }
+
+    /// Requires both filters to match. Chained calls extend a single `And`
+    /// rather than nesting.
+    pub fn and(self, other: Filter) -> Filter {
+        match self {
+            Filter::And(mut filters) => {
+                filters.push(other);
+                Filter::And(filters)
+            }
+            filter => Filter::And(vec![filter, other]),
+        }
+    }
+
+    /// Requires either filter to match. Chained calls extend a single `Or`
+    /// rather than nesting.
+    pub fn or(self, other: Filter) -> Filter {
+        match self {
+            Filter::Or(mut filters) => {
+                filters.push(other);
+                Filter::Or(filters)
+            }
+            filter => Filter::Or(vec![filter, other]),
+        }
+    }
+
+    /// Returns the canonical form of this filter, so two filters that differ
+    /// only in grouping, clause order or repeated clauses compare equal.
+    ///
+    /// Nested `And`s inside an `And` (and `Or`s inside an `Or`) are flattened,
+    /// repeated clauses are dropped, single-clause groups are replaced by their
+    /// clause, double negations cancel out, and the remaining clauses are
+    /// sorted by their query-string rendering. Empty groups are kept, since
+    /// they match everything (`And`) or nothing (`Or`).
+    pub fn normalize(&self) -> Filter {
+        match self {
+            Filter::And(filters) => normalize_group(filters, true),
+            Filter::Or(filters) => normalize_group(filters, false),
+            Filter::Not(inner) => match inner.normalize() {
+                Filter::Not(inner) => *inner,
+                inner => Filter::Not(Box::new(inner)),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+fn normalize_group(filters: &[Filter], is_and: bool) -> Filter {
+    let mut clauses: Vec<Filter> = Vec::new();
+    for filter in filters {
+        let flattened = match filter.normalize() {
+            Filter::And(inner) if is_and => inner,
+            Filter::Or(inner) if !is_and => inner,
+            single => vec![single],
+        };
+        for clause in flattened {
+            if !clauses.contains(&clause) {
+                clauses.push(clause);
+            }
+        }
+    }
+
+    if clauses.len() == 1 {
+        return clauses.remove(0);
+    }
+    clauses.sort_by_cached_key(|clause| clause.to_string());
+    if is_and {
+        Filter::And(clauses)
+    } else {
+        Filter::Or(clauses)
+    }
+}
+
+/// Negates a filter, so `!Filter::field("x").exists()` builds `Not(Exists)`.
+impl Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Filter {
+        Filter::Not(Box::new(self))
+    }
+}
+
+/// A predicate under construction, created by `Filter::field`. Each method
+/// finishes it into a `Filter` on that field.
+#[derive(Debug, Clone)]
+pub struct FilterBuilder {
+    field: FieldPath,
+    options: StringCompareOptions,
+}
+
+impl FilterBuilder {
+    /// Sets how string comparisons in `eq`, `is_in` and the pattern methods
+    /// treat case, Unicode normalization and whitespace.
+    pub fn with_options(mut self, options: StringCompareOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn eq(self, value: impl Into<Value>) -> Filter {
+        Filter::Equals { field: self.field, value: value.into(), options: self.options }
+    }
+
+    pub fn ne(self, value: impl Into<Value>) -> Filter {
+        Filter::NotEquals { field: self.field, value: value.into() }
+    }
+
+    pub fn gt(self, value: impl Into<Value>) -> Filter {
+        Filter::GreaterThan { field: self.field, value: value.into() }
+    }
+
+    pub fn gte(self, value: impl Into<Value>) -> Filter {
+        Filter::GreaterThanOrEqual { field: self.field, value: value.into() }
+    }
+
+    pub fn lt(self, value: impl Into<Value>) -> Filter {
+        Filter::LessThan { field: self.field, value: value.into() }
+    }
+
+    pub fn lte(self, value: impl Into<Value>) -> Filter {
+        Filter::LessThanOrEqual { field: self.field, value: value.into() }
+    }
+
+    /// Matches values from `low` to `high`, bounds included.
+    pub fn between(self, low: impl Into<Value>, high: impl Into<Value>) -> Filter {
+        Filter::Between { field: self.field, low: low.into(), high: high.into(), inclusive: true }
+    }
+
+    pub fn is_in<V: Into<Value>>(self, values: impl IntoIterator<Item = V>) -> Filter {
+        let values = values.into_iter().map(Into::into).collect();
+        Filter::In { field: self.field, values, options: self.options }
+    }
+
+    pub fn exists(self) -> Filter {
+        Filter::Exists { field: self.field }
+    }
+
+    pub fn not_exists(self) -> Filter {
+        Filter::NotExists { field: self.field }
+    }
+
+    pub fn matches(self, pattern: impl Into<String>) -> Filter {
+        Filter::Matches { field: self.field, pattern: pattern.into(), options: self.options }
+    }
+
+    pub fn starts_with(self, prefix: impl Into<String>) -> Filter {
+        Filter::StartsWith { field: self.field, prefix: prefix.into(), options: self.options }
+    }
+
+    pub fn ends_with(self, suffix: impl Into<String>) -> Filter {
+        Filter::EndsWith { field: self.field, suffix: suffix.into(), options: self.options }
+    }
+
+    pub fn contains(self, value: impl Into<Value>) -> Filter {
+        Filter::Contains { field: self.field, value: value.into() }
+    }
+
+    pub fn contains_all<V: Into<Value>>(self, values: impl IntoIterator<Item = V>) -> Filter {
+        let values = values.into_iter().map(Into::into).collect();
+        Filter::ContainsAll { field: self.field, values }
+    }
+
+    pub fn search(self, query: impl Into<String>) -> Filter {
+        Filter::TextSearch { field: self.field, query: query.into(), options: TextSearchOptions::default() }
+    }
+
+    pub fn within(self, region: GeoRegion) -> Filter {
+        Filter::GeoWithin { field: self.field, region }
+    }
+
+    pub fn near(self, point: GeoPoint, max_distance_meters: f64) -> Filter {
+        Filter::GeoNear { field: self.field, point, max_distance_meters }
+    }
+
+    pub fn is_type(self, value_type: ValueType) -> Filter {
+        Filter::TypeOf { field: self.field, value_type }
+    }
+}
+
+/// Renders a filter in a compact, human-readable query syntax, e.g.
+/// `age >= 21 AND (status = "active" OR role IN ["admin", "owner"])`.
+///
+/// Values are written as JSON. Nested `AND`/`OR` groups are always
+/// parenthesised, and groups with fewer than two members are written as
+/// `ALL(...)`/`ANY(...)`, so the text maps back onto exactly one filter tree;
+/// `Filter::parse` reads it back. Field names that are not plain paths are
+/// quoted with backticks.
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::Equals { field, value, options } => {
+                write_field(f, field)?;
+                write!(f, " = {value}")?;
+                write_compare_options(f, options)
+            }
+            Filter::NotEquals { field, value } => write_comparison(f, field, "!=", value),
+            Filter::GreaterThan { field, value } => write_comparison(f, field, ">", value),
+            Filter::LessThan { field, value } => write_comparison(f, field, "<", value),
+            Filter::GreaterThanOrEqual { field, value } => write_comparison(f, field, ">=", value),
+            Filter::LessThanOrEqual { field, value } => write_comparison(f, field, "<=", value),
+            Filter::In { field, values, options } => {
+                write_field(f, field)?;
+                f.write_str(" IN ")?;
+                write_list(f, values)?;
+                write_compare_options(f, options)
+            }
+            Filter::And(filters) => write_group(f, filters, "AND", "ALL"),
+            Filter::Or(filters) => write_group(f, filters, "OR", "ANY"),
+            Filter::Param(name) => {
+                f.write_str("$")?;
+                write_name(f, name, false)
+            }
+            Filter::Matches { field, pattern, options } => write_pattern(f, field, "MATCHES", pattern, options),
+            Filter::StartsWith { field, prefix, options } => write_pattern(f, field, "STARTS WITH", prefix, options),
+            Filter::EndsWith { field, suffix, options } => write_pattern(f, field, "ENDS WITH", suffix, options),
+            Filter::Exists { field } => {
+                write_field(f, field)?;
+                f.write_str(" EXISTS")
+            }
+            Filter::NotExists { field } => {
+                write_field(f, field)?;
+                f.write_str(" NOT EXISTS")
+            }
+            Filter::Between { field, low, high, inclusive } => {
+                write_field(f, field)?;
+                let exclusive = if *inclusive { "" } else { "EXCLUSIVE " };
+                write!(f, " BETWEEN {exclusive}{low} AND {high}")
+            }
+            Filter::Not(inner) => {
+                f.write_str("NOT ")?;
+                write_operand(f, inner)
+            }
+            Filter::Contains { field, value } => write_comparison(f, field, "CONTAINS", value),
+            Filter::ContainsAll { field, values } => {
+                write_field(f, field)?;
+                f.write_str(" CONTAINS ALL ")?;
+                write_list(f, values)
+            }
+            Filter::TextSearch { field, query, options } => {
+                write_field(f, field)?;
+                f.write_str(" SEARCH ")?;
+                write_string(f, query)?;
+                write_text_search_options(f, options)
+            }
+            Filter::GeoWithin { field, region } => {
+                write_field(f, field)?;
+                match region {
+                    GeoRegion::BoundingBox { south_west, north_east } => {
+                        write!(f, " WITHIN BOX({south_west}, {north_east})")
+                    }
+                    GeoRegion::Polygon(vertices) => {
+                        f.write_str(" WITHIN POLYGON(")?;
+                        for (i, vertex) in vertices.iter().enumerate() {
+                            if i > 0 {
+                                f.write_str(", ")?;
+                            }
+                            write!(f, "{vertex}")?;
+                        }
+                        f.write_str(")")
+                    }
+                }
+            }
+            Filter::GeoNear { field, point, max_distance_meters } => {
+                write_field(f, field)?;
+                write!(f, " NEAR {point} WITHIN {max_distance_meters:?}")
+            }
+            Filter::TypeOf { field, value_type } => {
+                write_field(f, field)?;
+                write!(f, " IS {}", value_type_name(*value_type))
+            }
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = ProtocolError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Filter::parse(input)
+    }
+}
+
+/// A recursive-descent parser over the syntax written by the `Display` impl.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, message: impl Into<String>) -> ProtocolError {
+        ProtocolError::InvalidFilter { position: self.pos, message: message.into() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Returns the bare word at the cursor without consuming it.
+    fn peek_word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let end = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        &rest[..end]
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let word = self.peek_word();
+        if word.eq_ignore_ascii_case(keyword) {
+            self.pos += word.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ProtocolError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected {keyword}")))
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(symbol) {
+            self.pos += symbol.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ProtocolError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{symbol}'")))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, ProtocolError> {
+        let mut filters = vec![self.parse_and()?];
+        while self.eat_keyword("OR") {
+            filters.push(self.parse_and()?);
+        }
+        Ok(if filters.len() == 1 { filters.remove(0) } else { Filter::Or(filters) })
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, ProtocolError> {
+        let mut filters = vec![self.parse_unary()?];
+        while self.eat_keyword("AND") {
+            filters.push(self.parse_unary()?);
+        }
+        Ok(if filters.len() == 1 { filters.remove(0) } else { Filter::And(filters) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, ProtocolError> {
+        if self.depth == MAX_PARSE_DEPTH {
+            return Err(self.error("filter is nested too deeply"));
+        }
+        self.depth += 1;
+        let filter = self.parse_primary();
+        self.depth -= 1;
+        filter
+    }
+
+    fn parse_primary(&mut self) -> Result<Filter, ProtocolError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.eat_symbol("(") {
+            let filter = self.parse_or()?;
+            self.expect_symbol(")")?;
+            return Ok(filter);
+        }
+        if self.eat_keyword("ALL") {
+            return Ok(Filter::And(self.parse_call_group()?));
+        }
+        if self.eat_keyword("ANY") {
+            return Ok(Filter::Or(self.parse_call_group()?));
+        }
+        if self.eat_symbol("$") {
+            return Ok(Filter::Param(self.parse_name(false)?));
+        }
+        let field = self.parse_field()?;
+        self.parse_predicate(field)
+    }
+
+    /// Parses the `(...)` of `ALL(...)`/`ANY(...)`, which holds at most one filter.
+    fn parse_call_group(&mut self) -> Result<Vec<Filter>, ProtocolError> {
+        self.expect_symbol("(")?;
+        if self.eat_symbol(")") {
+            return Ok(Vec::new());
+        }
+        let filter = self.parse_or()?;
+        self.expect_symbol(")")?;
+        Ok(vec![filter])
+    }
+
+    fn parse_predicate(&mut self, field: FieldPath) -> Result<Filter, ProtocolError> {
+        if self.eat_symbol("!=") {
+            return Ok(Filter::NotEquals { field, value: self.parse_value()? });
+        }
+        if self.eat_symbol(">=") {
+            return Ok(Filter::GreaterThanOrEqual { field, value: self.parse_value()? });
+        }
+        if self.eat_symbol("<=") {
+            return Ok(Filter::LessThanOrEqual { field, value: self.parse_value()? });
+        }
+        if self.eat_symbol("=") {
+            let value = self.parse_value()?;
+            let options = self.parse_compare_options()?;
+            return Ok(Filter::Equals { field, value, options });
+        }
+        if self.eat_symbol(">") {
+            return Ok(Filter::GreaterThan { field, value: self.parse_value()? });
+        }
+        if self.eat_symbol("<") {
+            return Ok(Filter::LessThan { field, value: self.parse_value()? });
+        }
+        if self.eat_keyword("IN") {
+            let values = self.parse_list()?;
+            let options = self.parse_compare_options()?;
+            return Ok(Filter::In { field, values, options });
+        }
+        if self.eat_keyword("MATCHES") {
+            let pattern = self.parse_string()?;
+            let options = self.parse_compare_options()?;
+            return Ok(Filter::Matches { field, pattern, options });
+        }
+        if self.eat_keyword("STARTS") {
+            self.expect_keyword("WITH")?;
+            let prefix = self.parse_string()?;
+            let options = self.parse_compare_options()?;
+            return Ok(Filter::StartsWith { field, prefix, options });
+        }
+        if self.eat_keyword("ENDS") {
+            self.expect_keyword("WITH")?;
+            let suffix = self.parse_string()?;
+            let options = self.parse_compare_options()?;
+            return Ok(Filter::EndsWith { field, suffix, options });
+        }
+        if self.eat_keyword("EXISTS") {
+            return Ok(Filter::Exists { field });
+        }
+        if self.eat_keyword("NOT") {
+            self.expect_keyword("EXISTS")?;
+            return Ok(Filter::NotExists { field });
+        }
+        if self.eat_keyword("BETWEEN") {
+            let inclusive = !self.eat_keyword("EXCLUSIVE");
+            let low = self.parse_value()?;
+            self.expect_keyword("AND")?;
+            let high = self.parse_value()?;
+            return Ok(Filter::Between { field, low, high, inclusive });
+        }
+        if self.eat_keyword("CONTAINS") {
+            if self.eat_keyword("ALL") {
+                return Ok(Filter::ContainsAll { field, values: self.parse_list()? });
+            }
+            return Ok(Filter::Contains { field, value: self.parse_value()? });
+        }
+        if self.eat_keyword("SEARCH") {
+            let query = self.parse_string()?;
+            let options = self.parse_text_search_options()?;
+            return Ok(Filter::TextSearch { field, query, options });
+        }
+        if self.eat_keyword("WITHIN") {
+            return Ok(Filter::GeoWithin { field, region: self.parse_region()? });
+        }
+        if self.eat_keyword("NEAR") {
+            let point = self.parse_point()?;
+            self.expect_keyword("WITHIN")?;
+            let max_distance_meters = self.parse_number()?;
+            return Ok(Filter::GeoNear { field, point, max_distance_meters });
+        }
+        if self.eat_keyword("IS") {
+            return Ok(Filter::TypeOf { field, value_type: self.parse_value_type()? });
+        }
+        Err(self.error("expected an operator"))
+    }
+
+    /// Parses a field name and checks that it is a well-formed `FieldPath`.
+    fn parse_field(&mut self) -> Result<FieldPath, ProtocolError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let name = self.parse_name(true)?;
+        FieldPath::parse(&name).map_err(|_| ProtocolError::InvalidFilter {
+            position: start,
+            message: format!("invalid field path '{name}'"),
+        })
+    }
+
+    /// Parses a bare or backtick-quoted name. Bare field names may not be
+    /// keywords; parameter names may.
+    fn parse_name(&mut self, reject_keywords: bool) -> Result<String, ProtocolError> {
+        if self.eat_symbol("`") {
+            let mut name = String::new();
+            loop {
+                let rest = self.rest();
+                let Some(end) = rest.find('`') else {
+                    return Err(self.error("unterminated quoted name"));
+                };
+                name.push_str(&rest[..end]);
+                self.pos += end + 1;
+                if !self.rest().starts_with('`') {
+                    return Ok(name);
+                }
+                name.push('`');
+                self.pos += 1;
+            }
+        }
+        let word = self.peek_word();
+        let plain = word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+        if !plain || (reject_keywords && is_keyword(word)) {
+            return Err(self.error("expected a field name"));
+        }
+        self.pos += word.len();
+        Ok(word.to_string())
+    }
+
+    /// Parses one JSON value. Outside of strings and brackets the value ends
+    /// at whitespace or punctuation of the filter syntax.
+    fn parse_value(&mut self) -> Result<Value, ProtocolError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let mut end = rest.len();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        for (i, c) in rest.char_indices() {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_string = false;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                continue;
+            }
+            match c {
+                '"' => in_string = true,
+                '[' | '{' => depth += 1,
+                ']' | '}' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                c if depth == 0 && (c.is_whitespace() || matches!(c, ',' | '(' | ')' | ']' | '}')) => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let value = serde_json::from_str(&rest[..end]).map_err(|e| self.error(format!("invalid value: {e}")))?;
+        self.pos += end;
+        Ok(value)
+    }
+
+    fn parse_string(&mut self) -> Result<String, ProtocolError> {
+        let start = self.pos;
+        match self.parse_value()? {
+            Value::String(s) => Ok(s),
+            _ => {
+                self.pos = start;
+                Err(self.error("expected a string"))
+            }
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<Value>, ProtocolError> {
+        let start = self.pos;
+        match self.parse_value()? {
+            Value::Array(values) => Ok(values),
+            _ => {
+                self.pos = start;
+                Err(self.error("expected a list"))
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<f64, ProtocolError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))).unwrap_or(rest.len());
+        let number = rest[..end].parse().map_err(|_| self.error("expected a number"))?;
+        self.pos += end;
+        Ok(number)
+    }
+
+    fn parse_value_type(&mut self) -> Result<ValueType, ProtocolError> {
+        let word = self.peek_word();
+        let value_type = [ValueType::Null, ValueType::Bool, ValueType::Number, ValueType::String, ValueType::Array, ValueType::Object]
+            .into_iter()
+            .find(|value_type| value_type_name(*value_type).eq_ignore_ascii_case(word))
+            .ok_or_else(|| self.error("expected a type: null, bool, number, string, array or object"))?;
+        self.pos += word.len();
+        Ok(value_type)
+    }
+
+    fn parse_point(&mut self) -> Result<GeoPoint, ProtocolError> {
+        self.expect_keyword("POINT")?;
+        self.expect_symbol("(")?;
+        let lat = self.parse_number()?;
+        self.expect_symbol(",")?;
+        let lon = self.parse_number()?;
+        self.expect_symbol(")")?;
+        Ok(GeoPoint { lat, lon })
+    }
+
+    fn parse_region(&mut self) -> Result<GeoRegion, ProtocolError> {
+        if self.eat_keyword("BOX") {
+            self.expect_symbol("(")?;
+            let south_west = self.parse_point()?;
+            self.expect_symbol(",")?;
+            let north_east = self.parse_point()?;
+            self.expect_symbol(")")?;
+            return Ok(GeoRegion::BoundingBox { south_west, north_east });
+        }
+        if self.eat_keyword("POLYGON") {
+            self.expect_symbol("(")?;
+            let mut vertices = Vec::new();
+            if !self.eat_symbol(")") {
+                loop {
+                    vertices.push(self.parse_point()?);
+                    if self.eat_symbol(")") {
+                        break;
+                    }
+                    self.expect_symbol(",")?;
+                }
+            }
+            return Ok(GeoRegion::Polygon(vertices));
+        }
+        Err(self.error("expected BOX or POLYGON"))
+    }
+
+    /// Parses an optional `WITH(name, name = number, ...)` clause into its
+    /// lowercased option names and values.
+    fn parse_with(&mut self) -> Result<Vec<(String, Option<f64>)>, ProtocolError> {
+        let mut options = Vec::new();
+        if !self.eat_keyword("WITH") {
+            return Ok(options);
+        }
+        self.expect_symbol("(")?;
+        if self.eat_symbol(")") {
+            return Ok(options);
+        }
+        loop {
+            let name = self.peek_word();
+            if name.is_empty() {
+                return Err(self.error("expected an option name"));
+            }
+            self.pos += name.len();
+            let value = if self.eat_symbol("=") { Some(self.parse_number()?) } else { None };
+            options.push((name.to_ascii_lowercase(), value));
+            if self.eat_symbol(")") {
+                return Ok(options);
+            }
+            self.expect_symbol(",")?;
+        }
+    }
+
+    fn parse_compare_options(&mut self) -> Result<StringCompareOptions, ProtocolError> {
+        let mut options = StringCompareOptions::default();
+        for (name, value) in self.parse_with()? {
+            match (name.as_str(), value) {
+                ("case_insensitive", None) => options.case_insensitive = true,
+                ("normalize_unicode", None) => options.normalize_unicode = true,
+                ("trim", None) => options.trim = true,
+                _ => return Err(self.error(format!("invalid comparison option '{name}'"))),
+            }
+        }
+        Ok(options)
+    }
+
+    fn parse_text_search_options(&mut self) -> Result<TextSearchOptions, ProtocolError> {
+        let mut options = TextSearchOptions::default();
+        for (name, value) in self.parse_with()? {
+            match (name.as_str(), value) {
+                ("match_all_terms", None) => options.match_all_terms = true,
+                ("prefix", None) => options.prefix = true,
+                ("min_score", Some(score)) => options.min_score = Some(score),
+                _ => return Err(self.error(format!("invalid search option '{name}'"))),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn value_type_name(value_type: ValueType) -> &'static str {
+    match value_type {
+        ValueType::Null => "null",
+        ValueType::Bool => "bool",
+        ValueType::Number => "number",
+        ValueType::String => "string",
+        ValueType::Array => "array",
+        ValueType::Object => "object",
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '[' | ']')
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+/// Renders a point as `POINT(lat, lon)`, the form used in filter query strings.
+impl fmt::Display for GeoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "POINT({:?}, {:?})", self.lat, self.lon)
+    }
+}
+
+fn write_comparison(f: &mut fmt::Formatter<'_>, field: &FieldPath, op: &str, value: &Value) -> fmt::Result {
+    write_field(f, field)?;
+    write!(f, " {op} {value}")
+}
+
+fn write_pattern(f: &mut fmt::Formatter<'_>, field: &FieldPath, op: &str, pattern: &str, options: &StringCompareOptions) -> fmt::Result {
+    write_field(f, field)?;
+    write!(f, " {op} ")?;
+    write_string(f, pattern)?;
+    write_compare_options(f, options)
+}
+
+fn write_group(f: &mut fmt::Formatter<'_>, filters: &[Filter], op: &str, function: &str) -> fmt::Result {
+    if filters.len() < 2 {
+        write!(f, "{function}(")?;
+        if let Some(filter) = filters.first() {
+            write!(f, "{filter}")?;
+        }
+        return f.write_str(")");
+    }
+    for (i, filter) in filters.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {op} ")?;
+        }
+        write_operand(f, filter)?;
+    }
+    Ok(())
+}
+
+/// Writes a filter nested inside `AND`, `OR` or `NOT`, parenthesising it when
+/// it is itself an infix group.
+fn write_operand(f: &mut fmt::Formatter<'_>, filter: &Filter) -> fmt::Result {
+    match filter {
+        Filter::And(filters) | Filter::Or(filters) if filters.len() >= 2 => write!(f, "({filter})"),
+        _ => write!(f, "{filter}"),
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, values: &[Value]) -> fmt::Result {
+    f.write_str("[")?;
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{value}")?;
+    }
+    f.write_str("]")
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str(&serde_json::to_string(s).map_err(|_| fmt::Error)?)
+}
+
+fn write_field(f: &mut fmt::Formatter<'_>, field: &FieldPath) -> fmt::Result {
+    write_name(f, &field.0, true)
+}
+
+/// Writes a field or parameter name bare when it is a plain path, and quoted
+/// with backticks (doubling any backtick inside) otherwise.
+fn write_name(f: &mut fmt::Formatter<'_>, name: &str, reject_keywords: bool) -> fmt::Result {
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(is_name_char)
+        && !(reject_keywords && is_keyword(name));
+    if plain {
+        f.write_str(name)
+    } else {
+        write!(f, "`{}`", name.replace('`', "``"))
+    }
+}
+
+fn write_compare_options(f: &mut fmt::Formatter<'_>, options: &StringCompareOptions) -> fmt::Result {
+    let flags = [
+        (options.case_insensitive, "case_insensitive"),
+        (options.normalize_unicode, "normalize_unicode"),
+        (options.trim, "trim"),
+    ];
+    let names: Vec<String> = flags.iter().filter(|(set, _)| *set).map(|(_, name)| name.to_string()).collect();
+    write_with(f, &names)
+}
+
+fn write_text_search_options(f: &mut fmt::Formatter<'_>, options: &TextSearchOptions) -> fmt::Result {
+    let mut names = Vec::new();
+    if options.match_all_terms {
+        names.push("match_all_terms".to_string());
+    }
+    if options.prefix {
+        names.push("prefix".to_string());
+    }
+    if let Some(min_score) = options.min_score {
+        names.push(format!("min_score = {min_score:?}"));
+    }
+    write_with(f, &names)
+}
+
+fn write_with(f: &mut fmt::Formatter<'_>, options: &[String]) -> fmt::Result {
+    if options.is_empty() {
+        return Ok(());
+    }
+    write!(f, " WITH({})", options.join(", "))
+}
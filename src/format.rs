@@ -0,0 +1,106 @@
+// File: src/format.rs
+// =============================================================================
+// This file defines the serialization formats a message payload can use.
+// bincode is the default and the most compact, but only Rust peers can
+// produce it; browser and Python clients use JSON, MessagePack or CBOR
+// instead, and JSON is handy for reading traffic while debugging.
+//
+// Connections start in bincode. Peers agree on another format by listing its
+// `feature_name` in `Hello::supported_features`, and each frame records the
+// format of its payload, so a reader never has to guess.
+
+use crate::error::ProtocolError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// How a message is serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WireFormat {
+    #[default]
+    Bincode,
+    Json,
+    /// MessagePack with named struct fields. Requires the `msgpack` feature.
+    MessagePack,
+    /// Requires the `cbor` feature.
+    Cbor,
+}
+
+impl WireFormat {
+    /// The handshake feature that advertises support for this format.
+    /// bincode needs none: every peer speaks it.
+    pub fn feature_name(&self) -> Option<&'static str> {
+        match self {
+            WireFormat::Bincode => None,
+            WireFormat::Json => Some("format:json"),
+            WireFormat::MessagePack => Some("format:msgpack"),
+            WireFormat::Cbor => Some("format:cbor"),
+        }
+    }
+
+    /// The formats this build of the crate can encode and decode.
+    pub fn supported() -> Vec<WireFormat> {
+        let mut supported = vec![WireFormat::Bincode, WireFormat::Json];
+        if cfg!(feature = "msgpack") {
+            supported.push(WireFormat::MessagePack);
+        }
+        if cfg!(feature = "cbor") {
+            supported.push(WireFormat::Cbor);
+        }
+        supported
+    }
+
+    pub fn encode<T: Serialize>(&self, message: &T) -> Result<Vec<u8>, ProtocolError> {
+        match self {
+            WireFormat::Bincode => Ok(bincode::serialize(message)?),
+            WireFormat::Json => Ok(serde_json::to_vec(message)?),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => rmp_serde::to_vec_named(message).map_err(encoding_error),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(message, &mut bytes).map_err(encoding_error)?;
+                Ok(bytes)
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(ProtocolError::UnsupportedFormat(other.id())),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, ProtocolError> {
+        match self {
+            WireFormat::Bincode => Ok(bincode::deserialize(bytes)?),
+            WireFormat::Json => Ok(serde_json::from_slice(bytes)?),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => rmp_serde::from_slice(bytes).map_err(encoding_error),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => ciborium::from_reader(bytes).map_err(encoding_error),
+            #[allow(unreachable_patterns)]
+            other => Err(ProtocolError::UnsupportedFormat(other.id())),
+        }
+    }
+
+    /// The id recorded in frame flags.
+    pub(crate) fn id(&self) -> u8 {
+        match self {
+            WireFormat::Bincode => 0,
+            WireFormat::Json => 1,
+            WireFormat::MessagePack => 2,
+            WireFormat::Cbor => 3,
+        }
+    }
+
+    pub(crate) fn from_id(id: u8) -> Result<WireFormat, ProtocolError> {
+        match id {
+            0 => Ok(WireFormat::Bincode),
+            1 => Ok(WireFormat::Json),
+            2 => Ok(WireFormat::MessagePack),
+            3 => Ok(WireFormat::Cbor),
+            _ => Err(ProtocolError::UnsupportedFormat(id)),
+        }
+    }
+}
+
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+fn encoding_error(error: impl std::error::Error + Send + Sync + 'static) -> ProtocolError {
+    ProtocolError::Encoding(Box::new(error))
+}
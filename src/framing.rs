@@ -0,0 +1,458 @@
+// File: src/framing.rs
+// =============================================================================
+// This file defines how protocol messages are delimited on a byte stream, so
+// every driver frames messages the same way. Each message is one or more
+// frames:
+//
+//     frame: MAGIC (u8) + FRAME_VERSION (u8) + flags (u8) + payload length
+//            (u32, little-endian) + payload [+ CRC-32 (u32, little-endian)]
+//
+// The message payload is the message serialized in the `WireFormat` named by
+// the top two bits of the flags byte, then compressed with the algorithm
+// named by the low bits (see `Compression`). The
+// magic and version bytes let a peer reject a stream that is not speaking
+// this protocol, or a newer framing, before trusting the length prefix.
+//
+// When the flags byte has `FLAG_CHECKSUM` set, the payload is followed by a
+// CRC-32 (IEEE) of the header and payload, so corruption is reported as such
+// instead of surfacing as a confusing decode error.
+//
+// A message payload longer than the peer's `max_frame_size` is split across
+// continuation frames: every frame but the last has `FLAG_CONTINUED` set, and
+// the reader concatenates their payloads. Readers bound both the size of each
+// frame and the size of the whole message (`ProtocolConfig`), so a peer
+// claiming a huge length is rejected before anything is allocated for it.
+
+use crate::config::ProtocolConfig;
+use crate::error::ProtocolError;
+use crate::format::WireFormat;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+
+/// The byte every frame starts with.
+pub const MAGIC: u8 = 0xAE;
+
+/// The frame format version written by `write_frame`.
+pub const FRAME_VERSION: u8 = 1;
+
+/// The size of the fixed header preceding each payload.
+pub const HEADER_LEN: usize = 7;
+
+/// The size of the checksum trailer on frames that carry one.
+pub const CHECKSUM_LEN: usize = 4;
+
+/// The bits of the flags byte that hold the `Compression` id.
+const COMPRESSION_MASK: u8 = 0x0f;
+
+/// The flag marking a frame that ends in a checksum trailer.
+const FLAG_CHECKSUM: u8 = 0x10;
+
+/// The flag marking a frame whose message continues in the next frame.
+const FLAG_CONTINUED: u8 = 0x20;
+
+/// The bits of the flags byte that hold the `WireFormat` id.
+const FORMAT_MASK: u8 = 0xc0;
+const FORMAT_SHIFT: u32 = 6;
+
+/// How `write_frame_with` and the codecs encode outgoing frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameOptions {
+    pub format: WireFormat,
+    pub compression: Compression,
+    /// Appends a CRC-32 trailer that the reader verifies.
+    pub checksum: bool,
+    /// Splits message payloads longer than this into continuation frames.
+    /// Should not exceed the reading peer's `max_frame_size`.
+    pub max_frame_len: usize,
+}
+
+impl Default for FrameOptions {
+    fn default() -> Self {
+        FrameOptions::from_config(&ProtocolConfig::default())
+    }
+}
+
+impl FrameOptions {
+    /// Uncompressed, unchecksummed bincode frames no larger than
+    /// `config.max_frame_size`.
+    pub fn from_config(config: &ProtocolConfig) -> Self {
+        FrameOptions {
+            format: WireFormat::Bincode,
+            compression: Compression::None,
+            checksum: false,
+            max_frame_len: FrameLimits::from_config(config).max_frame_len,
+        }
+    }
+}
+
+/// The sizes a reader accepts, taken from a `ProtocolConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FrameLimits {
+    pub(crate) max_frame_len: usize,
+    pub(crate) max_message_len: usize,
+}
+
+impl FrameLimits {
+    pub(crate) fn from_config(config: &ProtocolConfig) -> Self {
+        let to_usize = |size: u64| usize::try_from(size).unwrap_or(usize::MAX);
+        FrameLimits {
+            max_frame_len: to_usize(config.max_frame_size.as_bytes()),
+            max_message_len: to_usize(config.max_message_size.as_bytes()),
+        }
+    }
+
+    /// Checks the header of a frame adding `frame_len` bytes to a message
+    /// that already holds `message_len`.
+    fn check(&self, message_len: usize, frame_len: usize) -> Result<(), ProtocolError> {
+        if frame_len > self.max_frame_len {
+            return Err(ProtocolError::FrameTooLarge { len: frame_len, max: self.max_frame_len });
+        }
+        check_message_len(message_len.saturating_add(frame_len), self.max_message_len)
+    }
+}
+
+/// How a frame's payload is compressed. Peers agree on the algorithms they may
+/// use by listing `feature_name` in `Hello::supported_features`; a frame is
+/// only compressed with an algorithm from `HelloAck::negotiated_features`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Compression {
+    #[default]
+    None,
+    /// LZ4 block format with the uncompressed size prepended. Requires the
+    /// `lz4` feature.
+    Lz4,
+    /// A Zstandard frame. Requires the `zstd` feature.
+    Zstd,
+}
+
+impl Compression {
+    /// The handshake feature that advertises support for this algorithm.
+    pub fn feature_name(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Lz4 => Some("compression:lz4"),
+            Compression::Zstd => Some("compression:zstd"),
+        }
+    }
+
+    /// The algorithms this build of the crate can encode and decode.
+    pub fn supported() -> Vec<Compression> {
+        let mut supported = vec![Compression::None];
+        if cfg!(feature = "lz4") {
+            supported.push(Compression::Lz4);
+        }
+        if cfg!(feature = "zstd") {
+            supported.push(Compression::Zstd);
+        }
+        supported
+    }
+
+    fn id(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Compression, ProtocolError> {
+        match id {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Lz4),
+            2 => Ok(Compression::Zstd),
+            _ => Err(ProtocolError::UnsupportedCompression(id)),
+        }
+    }
+}
+
+/// Encodes `message` (a `Request`, `Response` or envelope) and writes it to
+/// `writer` as uncompressed bincode frames without checksums.
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<(), ProtocolError> {
+    write_frame_with(writer, message, FrameOptions::default())
+}
+
+/// Like `write_frame`, but serializes, compresses, checksums and splits the
+/// frames as `options` say.
+pub fn write_frame_with<W: Write, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+    options: FrameOptions,
+) -> Result<(), ProtocolError> {
+    writer.write_all(&encode_frames(message, options)?)?;
+    Ok(())
+}
+
+/// Reads one message from `reader` with the limits of
+/// `ProtocolConfig::default()`.
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<T, ProtocolError> {
+    read_frame_with(reader, &ProtocolConfig::default())
+}
+
+/// Reads the frames of one message from `reader`, verifying any checksums,
+/// and decodes it. Fails without allocating if a frame or the message would
+/// exceed the limits in `config`.
+pub fn read_frame_with<R: Read, T: DeserializeOwned>(reader: &mut R, config: &ProtocolConfig) -> Result<T, ProtocolError> {
+    let limits = FrameLimits::from_config(config);
+    let mut payload = Vec::new();
+    loop {
+        let mut header = [0u8; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        let (flags, len) = decode_header(&header)?;
+        let len = len as usize;
+        limits.check(payload.len(), len)?;
+
+        // Read through `take` so the buffer only grows as bytes actually arrive.
+        let start = payload.len();
+        reader.take(len as u64).read_to_end(&mut payload)?;
+        if payload.len() - start != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let mut trailer = [0u8; CHECKSUM_LEN];
+        let trailer = &mut trailer[..trailer_len(flags)];
+        reader.read_exact(trailer)?;
+        verify_checksum(&header, &payload[start..], trailer)?;
+
+        if flags & FLAG_CONTINUED == 0 {
+            return decode_payload(flags, &payload, limits.max_message_len);
+        }
+    }
+}
+
+/// Reads the frames of one message from `reader` without decoding them, for
+/// transports that hand messages over whole. The limits in `config` apply as
+/// in `read_frame_with`; checksums are verified when the frames are decoded.
+pub(crate) fn read_message_frames<R: Read>(reader: &mut R, config: &ProtocolConfig) -> Result<Vec<u8>, ProtocolError> {
+    let limits = FrameLimits::from_config(config);
+    let mut frames = Vec::new();
+    let mut message_len = 0;
+    loop {
+        let mut header = [0u8; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        let (flags, len) = decode_header(&header)?;
+        let len = len as usize;
+        limits.check(message_len, len)?;
+        message_len += len;
+        frames.extend_from_slice(&header);
+
+        let rest = len + trailer_len(flags);
+        let start = frames.len();
+        reader.take(rest as u64).read_to_end(&mut frames)?;
+        if frames.len() - start != rest {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        if flags & FLAG_CONTINUED == 0 {
+            return Ok(frames);
+        }
+    }
+}
+
+/// Encodes `message` as complete frames: headers, payloads and any trailers.
+pub(crate) fn encode_frames<T: Serialize>(message: &T, options: FrameOptions) -> Result<Vec<u8>, ProtocolError> {
+    let (mut flags, payload) = encode_payload(message, options.format, options.compression)?;
+    if options.checksum {
+        flags |= FLAG_CHECKSUM;
+    }
+    let mut chunks: Vec<&[u8]> = payload.chunks(options.max_frame_len.max(1)).collect();
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+    let mut frames = Vec::with_capacity(payload.len() + chunks.len() * (HEADER_LEN + CHECKSUM_LEN));
+    for (index, chunk) in chunks.iter().enumerate() {
+        let flags = if index + 1 < chunks.len() { flags | FLAG_CONTINUED } else { flags };
+        let start = frames.len();
+        frames.extend_from_slice(&encode_header(flags, payload_len(chunk)?));
+        frames.extend_from_slice(chunk);
+        if options.checksum {
+            let checksum = crc32(&frames[start..]);
+            frames.extend_from_slice(&checksum.to_le_bytes());
+        }
+    }
+    Ok(frames)
+}
+
+/// Returns the length of the first message's frames in `buf` once all of
+/// them have arrived, or `None` while more bytes are needed. Fails as soon as
+/// a header breaks `limits`, before the rest of the message is buffered.
+#[cfg(feature = "tokio")]
+pub(crate) fn complete_message_len(buf: &[u8], limits: &FrameLimits) -> Result<Option<usize>, ProtocolError> {
+    let mut offset = 0;
+    let mut message_len = 0;
+    loop {
+        let Some(header) = buf.get(offset..offset + HEADER_LEN) else {
+            return Ok(None);
+        };
+        let header: &[u8; HEADER_LEN] = header.try_into().expect("slice has HEADER_LEN bytes");
+        let (flags, len) = decode_header(header)?;
+        let len = len as usize;
+        limits.check(message_len, len)?;
+        message_len += len;
+        offset += HEADER_LEN + len + trailer_len(flags);
+        if flags & FLAG_CONTINUED == 0 {
+            return Ok((buf.len() >= offset).then_some(offset));
+        }
+    }
+}
+
+/// Verifies and decodes a message from `frames`, which must hold exactly its
+/// complete frames as measured by `complete_message_len`.
+#[cfg(feature = "tokio")]
+pub(crate) fn decode_frames<T: DeserializeOwned>(frames: &[u8], limits: &FrameLimits) -> Result<T, ProtocolError> {
+    let mut payload = Vec::new();
+    let mut rest = frames;
+    loop {
+        let header: [u8; HEADER_LEN] = rest[..HEADER_LEN].try_into().expect("complete frames");
+        let (flags, len) = decode_header(&header)?;
+        let (body, tail) = rest[HEADER_LEN..].split_at(len as usize);
+        let (trailer, tail) = tail.split_at(trailer_len(flags));
+        verify_checksum(&header, body, trailer)?;
+        rest = tail;
+        if flags & FLAG_CONTINUED == 0 {
+            if payload.is_empty() {
+                return decode_payload(flags, body, limits.max_message_len);
+            }
+            payload.extend_from_slice(body);
+            return decode_payload(flags, &payload, limits.max_message_len);
+        }
+        payload.extend_from_slice(body);
+    }
+}
+
+/// The number of trailer bytes following the payload of a frame with `flags`.
+fn trailer_len(flags: u8) -> usize {
+    if flags & FLAG_CHECKSUM != 0 {
+        CHECKSUM_LEN
+    } else {
+        0
+    }
+}
+
+/// Checks a frame's checksum trailer, which is empty if the frame has none.
+fn verify_checksum(header: &[u8; HEADER_LEN], payload: &[u8], trailer: &[u8]) -> Result<(), ProtocolError> {
+    let Ok(expected) = <[u8; CHECKSUM_LEN]>::try_from(trailer) else {
+        return Ok(());
+    };
+    let expected = u32::from_le_bytes(expected);
+    let actual = !crc32_update(crc32_update(!0, header), payload);
+    if actual != expected {
+        return Err(ProtocolError::ChecksumMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+fn encode_header(flags: u8, payload_len: u32) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[0] = MAGIC;
+    header[1] = FRAME_VERSION;
+    header[2] = flags;
+    header[3..].copy_from_slice(&payload_len.to_le_bytes());
+    header
+}
+
+/// Validates a frame header and returns its flags and the payload length it
+/// announces.
+fn decode_header(header: &[u8; HEADER_LEN]) -> Result<(u8, u32), ProtocolError> {
+    if header[0] != MAGIC {
+        return Err(ProtocolError::BadMagic);
+    }
+    if header[1] != FRAME_VERSION {
+        return Err(ProtocolError::UnsupportedVersion(header[1]));
+    }
+    Ok((header[2], u32::from_le_bytes([header[3], header[4], header[5], header[6]])))
+}
+
+fn payload_len(payload: &[u8]) -> Result<u32, ProtocolError> {
+    u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame payload exceeds u32::MAX bytes").into())
+}
+
+/// Serializes and compresses `message`, returning the frame flags to send
+/// with it.
+fn encode_payload<T: Serialize>(
+    message: &T,
+    format: WireFormat,
+    compression: Compression,
+) -> Result<(u8, Vec<u8>), ProtocolError> {
+    let encoded = format.encode(message)?;
+    let payload = match compression {
+        Compression::None => encoded,
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => lz4_flex::compress_prepend_size(&encoded),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => zstd::bulk::compress(&encoded, 0)?,
+        #[allow(unreachable_patterns)]
+        other => return Err(ProtocolError::UnsupportedCompression(other.id())),
+    };
+    Ok((format.id() << FORMAT_SHIFT | compression.id(), payload))
+}
+
+/// Decompresses a message payload according to `flags` and deserializes it.
+/// A compressed payload may expand to at most `max_message_len` bytes.
+fn decode_payload<T: DeserializeOwned>(flags: u8, payload: &[u8], max_message_len: usize) -> Result<T, ProtocolError> {
+    let format = WireFormat::from_id((flags & FORMAT_MASK) >> FORMAT_SHIFT)?;
+    match Compression::from_id(flags & COMPRESSION_MASK)? {
+        Compression::None => format.decode(payload),
+        compression => format.decode(&decompress(compression, payload, max_message_len)?),
+    }
+}
+
+fn check_message_len(len: usize, max: usize) -> Result<(), ProtocolError> {
+    if len > max {
+        return Err(ProtocolError::LimitExceeded { limit: "max_message_size", value: len, max });
+    }
+    Ok(())
+}
+
+#[allow(unused_variables)]
+fn decompress(compression: Compression, payload: &[u8], max_len: usize) -> Result<Vec<u8>, ProtocolError> {
+    match compression {
+        Compression::None => Ok(payload.to_vec()),
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => {
+            let len = payload.get(..4).map(|prefix| u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]));
+            match len {
+                Some(len) => check_message_len(len as usize, max_len)?,
+                None => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            }
+            lz4_flex::decompress_size_prepended(payload).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            // Stream through `take` so a decompression bomb stops at the limit.
+            let mut decoded = Vec::new();
+            zstd::stream::read::Decoder::new(payload)?
+                .take((max_len as u64).saturating_add(1))
+                .read_to_end(&mut decoded)?;
+            check_message_len(decoded.len(), max_len)?;
+            Ok(decoded)
+        }
+        #[allow(unreachable_patterns)]
+        other => Err(ProtocolError::UnsupportedCompression(other.id())),
+    }
+}
+
+/// The CRC-32 (IEEE 802.3) lookup table, built at compile time.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// The CRC-32 (IEEE 802.3) of `bytes`.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !crc32_update(!0, bytes)
+}
+
+fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &byte| CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
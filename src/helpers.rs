@@ -0,0 +1,60 @@
+// File: src/helpers.rs
+// =============================================================================
+// This file provides shortcuts for the most common requests, so simple
+// scripts can talk to a server without learning every `Request` field. Each
+// helper fills the remaining fields with their plain defaults; anything more
+// specific is built from the `Request` variants directly.
+
+use crate::error::ProtocolError;
+use crate::format::WireFormat;
+use crate::framing::{encode_frames, read_frame, FrameOptions};
+use crate::types::{Filter, Record};
+use crate::{Request, Response};
+
+/// Fetches one record by id, answered with `Response::Record`.
+pub fn get(db: impl Into<String>, collection: impl Into<String>, record_id: impl Into<String>) -> Request {
+    Request::GetRecord {
+        db_name: Some(db.into()),
+        collection: collection.into(),
+        record_id: record_id.into(),
+        with_version: false,
+    }
+}
+
+/// Finds every record matching `filter`, answered with `Response::RecordSet`.
+pub fn find(db: impl Into<String>, collection: impl Into<String>, filter: Filter) -> Request {
+    Request::FindRecords { db_name: Some(db.into()), collection: collection.into(), filter, options: None }
+}
+
+/// Inserts a record under a server-generated id, answered with
+/// `Response::RecordCreated`.
+pub fn insert(db: impl Into<String>, collection: impl Into<String>, data: Record) -> Request {
+    Request::InsertRecord { db_name: Some(db.into()), collection: collection.into(), data, ttl: None }
+}
+
+/// Deletes one record by id, answered with `Response::RecordDeleted`.
+pub fn delete(db: impl Into<String>, collection: impl Into<String>, record_id: impl Into<String>) -> Request {
+    Request::DeleteRecord {
+        db_name: Some(db.into()),
+        collection: collection.into(),
+        record_id: record_id.into(),
+        cascade: false,
+        expected_version: None,
+        return_document: None,
+    }
+}
+
+/// Encodes a request as frames in `format`, ready to write to a connection.
+/// `WireFormat::Bincode` is what every peer speaks by default, but bincode
+/// cannot decode the JSON values in filters and records, so requests that
+/// carry them need a self-describing format the connection has negotiated,
+/// such as `WireFormat::Json` after `format:json`.
+pub fn encode_request(request: &Request, format: WireFormat) -> Result<Vec<u8>, ProtocolError> {
+    encode_frames(request, FrameOptions { format, ..Default::default() })
+}
+
+/// Decodes a response from the frames read off a connection, in whichever
+/// format they record.
+pub fn parse_response(mut bytes: &[u8]) -> Result<Response, ProtocolError> {
+    read_frame(&mut bytes)
+}
@@ -0,0 +1,133 @@
+// File: src/journal.rs
+// =============================================================================
+// This file defines the on-disk journal format used to capture request
+// traffic so it can be replayed later for load testing and debugging.
+//
+// A journal is a header followed by any number of length-prefixed entries:
+//
+//     header: b"AEJRNL" + format version (u8)
+//     entry:  payload length (u32, little-endian) + JSON-encoded `JournalEntry`
+//
+// Entries are JSON rather than bincode because requests that carry records
+// hold `serde_json::Value`s, which bincode cannot decode.
+
+use crate::error::ProtocolError;
+use crate::Request;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The bytes every journal starts with.
+pub const MAGIC: &[u8; 6] = b"AEJRNL";
+
+/// The journal format version written by `JournalWriter`.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// A single captured request.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct JournalEntry {
+    pub request_id: u64,
+    /// When the request was captured, in microseconds since the Unix epoch.
+    pub timestamp_micros: u64,
+    pub request: Request,
+}
+
+impl JournalEntry {
+    /// Creates an entry stamped with the current wall-clock time.
+    pub fn now(request_id: u64, request: Request) -> Self {
+        let timestamp_micros = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or(0);
+        JournalEntry { request_id, timestamp_micros, request }
+    }
+}
+
+/// Appends entries to a journal.
+pub struct JournalWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> JournalWriter<W> {
+    /// Writes the journal header to `inner` and returns a writer for entries.
+    pub fn new(mut inner: W) -> Result<Self, ProtocolError> {
+        inner.write_all(MAGIC)?;
+        inner.write_all(&[FORMAT_VERSION])?;
+        Ok(JournalWriter { inner })
+    }
+
+    /// Appends one entry to the journal.
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<(), ProtocolError> {
+        let payload = serde_json::to_vec(entry)?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "journal entry exceeds u32::MAX bytes"))?;
+        self.inner.write_all(&len.to_le_bytes())?;
+        self.inner.write_all(&payload)?;
+        Ok(())
+    }
+
+    /// Flushes any buffered entries to the underlying writer.
+    pub fn flush(&mut self) -> Result<(), ProtocolError> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads entries back out of a journal, in the order they were written.
+pub struct JournalReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> JournalReader<R> {
+    /// Reads and validates the journal header from `inner`.
+    pub fn new(mut inner: R) -> Result<Self, ProtocolError> {
+        let mut magic = [0u8; MAGIC.len()];
+        inner.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(ProtocolError::BadMagic);
+        }
+        let mut version = [0u8; 1];
+        inner.read_exact(&mut version)?;
+        if version[0] != FORMAT_VERSION {
+            return Err(ProtocolError::UnsupportedVersion(version[0]));
+        }
+        Ok(JournalReader { inner })
+    }
+
+    /// Reads the next entry, or `None` at a clean end of the journal.
+    pub fn next_entry(&mut self) -> Result<Option<JournalEntry>, ProtocolError> {
+        let mut len = [0u8; 4];
+        let mut filled = 0;
+        while filled < len.len() {
+            match self.inner.read(&mut len[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        // Read through `take` so a corrupt length cannot force a huge allocation up front.
+        let len = u32::from_le_bytes(len) as u64;
+        let mut payload = Vec::new();
+        (&mut self.inner).take(len).read_to_end(&mut payload)?;
+        if payload.len() as u64 != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(Some(serde_json::from_slice(&payload)?))
+    }
+}
+
+impl<R: Read> Iterator for JournalReader<R> {
+    type Item = Result<JournalEntry, ProtocolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().transpose()
+    }
+}
@@ -11,19 +11,54 @@
 //! structures, serialized using `bincode` for maximum performance.
 
 // Declare the modules that make up our library.
+pub mod codec;
+pub mod config;
+pub mod corpus;
+pub mod error;
+pub mod filter;
+pub mod format;
+pub mod framing;
+pub mod helpers;
+pub mod journal;
+pub mod record;
 pub mod request;
 pub mod response;
+#[cfg(feature = "tokio")]
+pub mod tokio_codec;
+pub mod transport;
 pub mod types;
+pub mod version;
+pub mod view;
 
 // Re-export the most important structs and enums for convenience.
-pub use request::Request;
-pub use response::Response;
-pub use types::{BatchRequest, BatchResponse, DbStats, Direction, Filter, QueryOptions, Record, RecordSet};
+pub use codec::{Codec, CodecHooks, Instrument, Message, TrafficDirection};
+pub use config::ProtocolConfig;
+pub use error::ProtocolError;
+pub use filter::{bind_params, compare_values, FilterBuilder};
+pub use format::WireFormat;
+pub use framing::{read_frame, read_frame_with, write_frame, write_frame_with, Compression, FrameOptions};
+pub use helpers::{delete, encode_request, find, get, insert, parse_response};
+pub use journal::{JournalEntry, JournalReader, JournalWriter};
+pub use record::RecordExt;
+pub use request::{Request, RequestEnvelope};
+pub use response::{Response, ResponseEnvelope};
+pub use types::{EXPIRES_AT_FIELD, AclAccess, Accumulator, AggregateResult, Aggregation, AggregationPipeline, BatchRequest, BatchResponse, BatchWriteRequest, BatchWriteResponse, BulkWriteError, ByteSize, CollectionDefaults, CollectionLifetime, CollectionOptions, CollectionSchema, ConfirmationToken, Corruption, ConnectionId, ConnectionInfo, Cursor, DbStats, Deprecation, DistanceMetric, Direction, DryRunReport, DurationMs, ErrorCode, ExplainFormat, Expr, FieldPath, Filter, GeoPoint, GeoRegion, Granularity, IndexInfo, IndexKind, IndexState, IsolationLevel, JobId, JobStatus, ListOptions, OperationId, OperationInfo, PathSegment, PipelineStage, PlanStage, PreparedToken, Projection, ProtocolVersion, Page, PageInfo, ProjectionEntry, QueryOptions, QueryPlan, Record, RecordAcl, RecordSet, RefreshPolicy, ReturnDocument, ScanThrottle, ScanType, ScoredRecord, SortExpr, StreamId, StringCompareOptions, TaggedRecord, TextSearchOptions, TimeBucket, TimeRange, TimeSeriesOptions, TransactionId, TransactionOptions, TransactionState, TransactionStatus, UpdateOp, UpdateOps, ValueType, VerificationReport, VerifyMode, ViewStats, WriteFailure, WriteOperation, WriteReceipt, WriteResult};
 pub use response::QueryMetrics;
+pub use version::{is_compatible, Capability, Compatibility, PROTOCOL_VERSION};
+pub use view::{RecordRef, ResponseRef};
+#[cfg(feature = "tokio")]
+pub use tokio_codec::{AetherCodec, AetherServerCodec, FramedCodec};
+pub use transport::{StreamTransport, Transport};
+#[cfg(feature = "memory")]
+pub use transport::MemoryTransport;
+#[cfg(feature = "tcp")]
+pub use transport::TcpTransport;
+#[cfg(all(unix, feature = "unix"))]
+pub use transport::UnixTransport;
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{BatchRequest, BatchResponse, DbStats, Direction, Filter, QueryOptions, Record, RecordSet};
+    use crate::types::{BatchRequest, BatchResponse, DbStats, Direction, Expr, Filter, Projection, ProjectionEntry, QueryOptions, Record, RecordSet, SortExpr};
     use crate::{Request, Response};
     use serde_json::json;
     use std::collections::HashMap;
@@ -74,6 +109,15 @@ mod tests {
         
         let recordset = RecordSet {
             records: vec![record1, record2],
+            ..Default::default()
+        };
+        test_serialization_json(recordset);
+
+        // A result cut short by a response size budget.
+        let recordset = RecordSet {
+            records: vec![],
+            truncated: true,
+            continuation: Some("page-2".to_string()),
         };
         
         test_serialization_json(recordset);
@@ -84,45 +128,132 @@ mod tests {
         // Test each Filter variant
         let filters = vec![
             Filter::Equals {
-                field: "status".to_string(),
+                field: "status".into(),
                 value: json!("active"),
+                options: Default::default(),
             },
             Filter::NotEquals {
-                field: "deleted".to_string(),
+                field: "deleted".into(),
                 value: json!(true),
             },
             Filter::GreaterThan {
-                field: "age".to_string(),
-                value: 18.0,
+                field: "age".into(),
+                value: json!(18.0),
             },
             Filter::LessThan {
-                field: "price".to_string(),
-                value: 100.0,
+                field: "price".into(),
+                value: json!(100.0),
             },
             Filter::In {
-                field: "category".to_string(),
+                field: "category".into(),
                 values: vec![json!("electronics"), json!("books")],
+                options: Default::default(),
             },
             Filter::And(vec![
                 Filter::Equals {
-                    field: "active".to_string(),
+                    field: "active".into(),
                     value: json!(true),
+                    options: Default::default(),
                 },
                 Filter::GreaterThan {
-                    field: "score".to_string(),
-                    value: 70.0,
+                    field: "score".into(),
+                    value: json!(70.0),
                 },
             ]),
             Filter::Or(vec![
                 Filter::Equals {
-                    field: "type".to_string(),
+                    field: "type".into(),
                     value: json!("premium"),
+                    options: Default::default(),
                 },
                 Filter::Equals {
-                    field: "special".to_string(),
+                    field: "special".into(),
                     value: json!(true),
+                    options: Default::default(),
                 },
             ]),
+            Filter::Param("owner".to_string()),
+            Filter::Matches {
+                field: "email".into(),
+                pattern: r"^[^@]+@example\.com$".to_string(),
+                options: crate::types::StringCompareOptions {
+                    case_insensitive: true,
+                    normalize_unicode: true,
+                    trim: true,
+                },
+            },
+            Filter::StartsWith {
+                field: "name".into(),
+                prefix: "Jo".to_string(),
+                options: Default::default(),
+            },
+            Filter::EndsWith {
+                field: "file".into(),
+                suffix: ".rs".to_string(),
+                options: Default::default(),
+            },
+            Filter::Exists { field: "email".into() },
+            Filter::NotExists { field: "deleted_at".into() },
+            Filter::Between {
+                field: "age".into(),
+                low: json!(18),
+                high: json!(65),
+                inclusive: true,
+            },
+            Filter::GreaterThanOrEqual {
+                field: "age".into(),
+                value: json!(21.0),
+            },
+            Filter::LessThanOrEqual {
+                field: "price".into(),
+                value: json!(99.99),
+            },
+            Filter::Not(Box::new(Filter::In {
+                field: "status".into(),
+                values: vec![json!("banned"), json!("deleted")],
+                options: Default::default(),
+            })),
+            Filter::Contains {
+                field: "tags".into(),
+                value: json!("rust"),
+            },
+            Filter::ContainsAll {
+                field: "tags".into(),
+                values: vec![json!("rust"), json!("database")],
+            },
+            Filter::TextSearch {
+                field: "body".into(),
+                query: "embedded database".to_string(),
+                options: crate::types::TextSearchOptions {
+                    match_all_terms: true,
+                    prefix: false,
+                    min_score: Some(0.5),
+                },
+            },
+            Filter::GeoWithin {
+                field: "location".into(),
+                region: crate::types::GeoRegion::BoundingBox {
+                    south_west: crate::types::GeoPoint { lat: 40.70, lon: -74.02 },
+                    north_east: crate::types::GeoPoint { lat: 40.88, lon: -73.91 },
+                },
+            },
+            Filter::GeoWithin {
+                field: "location".into(),
+                region: crate::types::GeoRegion::Polygon(vec![
+                    crate::types::GeoPoint { lat: 0.0, lon: 0.0 },
+                    crate::types::GeoPoint { lat: 0.0, lon: 1.0 },
+                    crate::types::GeoPoint { lat: 1.0, lon: 0.0 },
+                ]),
+            },
+            Filter::GeoNear {
+                field: "location".into(),
+                point: crate::types::GeoPoint { lat: 51.5074, lon: -0.1278 },
+                max_distance_meters: 500.0,
+            },
+            Filter::TypeOf {
+                field: "age".into(),
+                value_type: crate::types::ValueType::String,
+            },
         ];
         
         for filter in filters {
@@ -130,16 +261,381 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_page_serialization() {
+        test_serialization_json(crate::types::Page {
+            items: vec![Record::from([("id".to_string(), json!(1))])],
+            page_info: crate::types::PageInfo {
+                next_page_token: Some("p2".to_string()),
+                total_count: Some(40),
+            },
+        });
+        test_serialization_bincode(crate::types::Page {
+            items: vec!["users".to_string(), "orders".to_string()],
+            page_info: crate::types::PageInfo::default(),
+        });
+    }
+
+    #[test]
+    fn test_string_compare_options_default() {
+        // Filters written before compare options existed still decode, with exact comparison.
+        let filter: Filter = serde_json::from_str(r#"{"Equals":{"field":"email","value":"a@b.c"}}"#).unwrap();
+        assert_eq!(
+            filter,
+            Filter::Equals {
+                field: "email".into(),
+                value: json!("a@b.c"),
+                options: crate::types::StringCompareOptions::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_compare_values() {
+        use crate::compare_values;
+        use std::cmp::Ordering;
+
+        // Cross-type order: null < bool < number < string < array < object.
+        let ascending = [
+            json!(null),
+            json!(false),
+            json!(true),
+            json!(-5),
+            json!(2.5),
+            json!(3),
+            json!(""),
+            json!("a"),
+            json!([]),
+            json!([1]),
+            json!({}),
+        ];
+        for pair in ascending.windows(2) {
+            assert_eq!(compare_values(&pair[0], &pair[1]), Ordering::Less, "{} < {}", pair[0], pair[1]);
+        }
+
+        // Integers compare exactly, beyond what f64 can represent.
+        assert_eq!(compare_values(&json!(i64::MAX - 1), &json!(i64::MAX)), Ordering::Less);
+        assert_eq!(compare_values(&json!(u64::MAX), &json!(-1)), Ordering::Greater);
+        assert_eq!(compare_values(&json!(2), &json!(2.0)), Ordering::Equal);
+
+        // ISO-8601 timestamps order chronologically.
+        assert_eq!(compare_values(&json!("2023-12-31T23:59:59Z"), &json!("2024-01-01T00:00:00Z")), Ordering::Less);
+
+        assert_eq!(compare_values(&json!([1, 2]), &json!([1, 3])), Ordering::Less);
+        assert_eq!(compare_values(&json!({ "a": 1, "b": 2 }), &json!({ "b": 2, "a": 1 })), Ordering::Equal);
+        assert_eq!(compare_values(&json!({ "a": 1 }), &json!({ "a": 1, "b": 0 })), Ordering::Less);
+    }
+
+    #[test]
+    fn test_filter_display() {
+        let filter = Filter::And(vec![
+            Filter::GreaterThanOrEqual {
+                field: "age".into(),
+                value: json!(21),
+            },
+            Filter::Or(vec![
+                Filter::Equals {
+                    field: "status".into(),
+                    value: json!("active"),
+                    options: Default::default(),
+                },
+                Filter::In {
+                    field: "role".into(),
+                    values: vec![json!("admin"), json!("owner")],
+                    options: Default::default(),
+                },
+            ]),
+            Filter::Not(Box::new(Filter::Exists { field: "address.deleted_at".into() })),
+        ]);
+        assert_eq!(
+            filter.to_query_string(),
+            r#"age >= 21 AND (status = "active" OR role IN ["admin", "owner"]) AND NOT address.deleted_at EXISTS"#
+        );
+
+        let filter = Filter::StartsWith {
+            field: "user name".into(),
+            prefix: "Jo".to_string(),
+            options: crate::types::StringCompareOptions {
+                case_insensitive: true,
+                trim: true,
+                ..Default::default()
+            },
+        };
+        assert_eq!(filter.to_string(), r#"`user name` STARTS WITH "Jo" WITH(case_insensitive, trim)"#);
+
+        let filter = Filter::Between {
+            field: "in".into(),
+            low: json!(1),
+            high: json!(5),
+            inclusive: false,
+        };
+        assert_eq!(filter.to_string(), "`in` BETWEEN EXCLUSIVE 1 AND 5");
+
+        let filter = Filter::GeoNear {
+            field: "location".into(),
+            point: crate::types::GeoPoint { lat: 51.5, lon: -0.25 },
+            max_distance_meters: 500.0,
+        };
+        assert_eq!(filter.to_string(), "location NEAR POINT(51.5, -0.25) WITHIN 500.0");
+
+        assert_eq!(Filter::And(vec![]).to_string(), "ALL()");
+        assert_eq!(Filter::Or(vec![Filter::Param("owner".to_string())]).to_string(), "ANY($owner)");
+    }
+
+    #[test]
+    fn test_filter_parse() {
+        let parsed = Filter::parse(r#"age > 21 and (status = "active" OR `in` IN [1, 2]) AND NOT deleted_at EXISTS"#)
+            .expect("valid filter");
+        let expected = Filter::field("age")
+            .gt(21)
+            .and(Filter::field("status").eq("active").or(Filter::field("in").is_in([1, 2])))
+            .and(!Filter::field("deleted_at").exists());
+        assert_eq!(parsed, expected);
+
+        let mut filters = crate::corpus::filters();
+        filters.extend([
+            expected,
+            Filter::Or(vec![Filter::And(vec![Filter::Param("owner".to_string())])]),
+            Filter::And(vec![Filter::field("a").ne(json!([1, "x)"])), Filter::And(vec![Filter::field("b").lte(2.5), Filter::field("c").lt(-1)])]),
+            Filter::Not(Box::new(Filter::Not(Box::new(Filter::field("items[0].sku").not_exists())))),
+            Filter::field("`odd` name").with_options(crate::types::StringCompareOptions { trim: true, ..Default::default() }).matches("^a\\\"b$"),
+            Filter::field("email").ends_with("@example.com"),
+            Filter::Between { field: "score".into(), low: json!(1), high: json!({ "x": null }), inclusive: false },
+            Filter::field("tags").contains_all(["a", "b"]).or(Filter::field("tags").contains(json!({ "k": [] }))),
+            Filter::TextSearch {
+                field: "body".into(),
+                query: "rust protocol".to_string(),
+                options: crate::types::TextSearchOptions { match_all_terms: true, prefix: true, min_score: Some(0.5) },
+            },
+            Filter::field("loc").within(crate::types::GeoRegion::BoundingBox {
+                south_west: crate::types::GeoPoint { lat: -1.5, lon: 2.0 },
+                north_east: crate::types::GeoPoint { lat: 3.0, lon: 1e300 },
+            }),
+            Filter::field("loc").within(crate::types::GeoRegion::Polygon(vec![])),
+            Filter::field("loc").near(crate::types::GeoPoint { lat: 51.5, lon: -0.25 }, 500.0),
+            Filter::field("zip").is_type(crate::types::ValueType::Number).and(Filter::field("is").is_type(crate::types::ValueType::Null)),
+        ]);
+        for filter in filters {
+            let text = filter.to_string();
+            assert_eq!(Filter::parse(&text).expect(&text), filter, "{text}");
+        }
+
+        for invalid in ["", "age >", "age > 21 AND", "and = 1", "a = 1 WITH(shout)", "a IN 1", "a IS date", "(a = 1", "a = 1 b = 2", "a..b = 1", "items[x] = 1"] {
+            assert!(matches!(Filter::parse(invalid), Err(crate::ProtocolError::InvalidFilter { .. })), "{invalid}");
+        }
+        assert_eq!(
+            Filter::parse("items[0].sku = 1").unwrap(),
+            Filter::Equals { field: crate::types::FieldPath::parse("items[0].sku").unwrap(), value: json!(1), options: Default::default() }
+        );
+        assert!(matches!(Filter::parse("a = 1 AND a..b = 1"), Err(crate::ProtocolError::InvalidFilter { position: 10, .. })));
+        let deep = format!("{}a = 1{}", "(".repeat(1000), ")".repeat(1000));
+        assert!(Filter::parse(&deep).is_err());
+    }
+
+    #[test]
+    fn test_filter_builder() {
+        let built = Filter::field("age")
+            .gt(21)
+            .and(Filter::field("active").eq(true))
+            .and(!Filter::field("role").is_in(["banned", "suspended"]));
+        let expected = Filter::And(vec![
+            Filter::GreaterThan {
+                field: "age".into(),
+                value: json!(21),
+            },
+            Filter::Equals {
+                field: "active".into(),
+                value: json!(true),
+                options: Default::default(),
+            },
+            Filter::Not(Box::new(Filter::In {
+                field: "role".into(),
+                values: vec![json!("banned"), json!("suspended")],
+                options: Default::default(),
+            })),
+        ]);
+        assert_eq!(built, expected);
+
+        let options = crate::types::StringCompareOptions { case_insensitive: true, ..Default::default() };
+        let built = Filter::field("email")
+            .with_options(options)
+            .eq("alice@example.com")
+            .or(Filter::field("tags").contains("vip"));
+        assert_eq!(
+            built,
+            Filter::Or(vec![
+                Filter::Equals {
+                    field: "email".into(),
+                    value: json!("alice@example.com"),
+                    options,
+                },
+                Filter::Contains {
+                    field: "tags".into(),
+                    value: json!("vip"),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_filter_normalize() {
+        let active = Filter::field("active").eq(true);
+        let adult = Filter::field("age").gte(18);
+        let admin = Filter::field("role").eq("admin");
+
+        let messy = Filter::And(vec![
+            Filter::And(vec![adult.clone(), Filter::Or(vec![active.clone()])]),
+            !!adult.clone(),
+            Filter::Or(vec![admin.clone(), Filter::Or(vec![active.clone(), admin.clone()])]),
+        ]);
+        let reordered = Filter::And(vec![
+            Filter::Or(vec![active.clone(), admin.clone()]),
+            active.clone(),
+            adult.clone(),
+        ]);
+        let expected = Filter::And(vec![
+            active.clone(),
+            Filter::Or(vec![active.clone(), admin.clone()]),
+            adult.clone(),
+        ]);
+        assert_eq!(messy.normalize(), expected);
+        assert_eq!(reordered.normalize(), expected);
+        assert_eq!(expected.normalize(), expected);
+
+        assert_eq!(Filter::Or(vec![Filter::And(vec![admin.clone()])]).normalize(), admin);
+        assert_eq!(Filter::And(vec![Filter::And(Vec::new())]).normalize(), Filter::And(Vec::new()));
+    }
+
+    #[test]
+    fn test_bind_params() {
+        let template = Filter::And(vec![
+            Filter::Equals {
+                field: "status".into(),
+                value: json!("active"),
+                options: Default::default(),
+            },
+            Filter::Or(vec![Filter::Not(Box::new(Filter::Param("owner".to_string())))]),
+        ]);
+        let owner = Filter::Equals {
+            field: "owner_id".into(),
+            value: json!(42),
+            options: Default::default(),
+        };
+
+        let mut values = HashMap::new();
+        values.insert("owner".to_string(), owner.clone());
+        let bound = crate::bind_params(&template, &values).expect("all params are bound");
+        assert_eq!(
+            bound,
+            Filter::And(vec![
+                Filter::Equals {
+                    field: "status".into(),
+                    value: json!("active"),
+                    options: Default::default(),
+                },
+                Filter::Or(vec![Filter::Not(Box::new(owner))]),
+            ])
+        );
+
+        let missing = crate::bind_params(&template, &HashMap::new());
+        assert!(matches!(missing, Err(crate::ProtocolError::UnboundParam(name)) if name == "owner"));
+    }
+
+    #[test]
+    fn test_field_path() {
+        use crate::types::{FieldPath, PathSegment};
+
+        let path = FieldPath::parse("items[0].sku").expect("valid path");
+        assert_eq!(
+            path.segments().unwrap(),
+            vec![
+                PathSegment::Key("items".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Key("sku".to_string()),
+            ]
+        );
+
+        for invalid in ["", ".a", "a.", "a..b", "[0]", "a[", "a[]", "a[x]", "a]b", "a[0]b"] {
+            assert!(FieldPath::parse(invalid).is_err(), "{invalid:?} should be rejected");
+        }
+
+        let mut record = Record::new();
+        record.insert("address".to_string(), json!({ "city": "Lisbon" }));
+        record.insert("items".to_string(), json!([{ "sku": "A-1" }, { "sku": "B-2" }]));
+        assert_eq!(FieldPath::from("address.city").resolve(&record), Some(&json!("Lisbon")));
+        assert_eq!(FieldPath::from("items[1].sku").resolve(&record), Some(&json!("B-2")));
+        assert_eq!(FieldPath::from("items[2].sku").resolve(&record), None);
+        assert_eq!(FieldPath::from("address.zip").resolve(&record), None);
+    }
+
+    #[test]
+    fn test_record_acl() {
+        use crate::types::{AclAccess, RecordAcl};
+
+        let acl = RecordAcl {
+            owners: vec!["alice".to_string()],
+            readers: vec!["bob".to_string()],
+            writers: vec!["carol".to_string()],
+        };
+        let mut record = Record::new();
+        assert_eq!(RecordAcl::from_record(&record).unwrap(), None);
+        acl.apply_to(&mut record).unwrap();
+        assert_eq!(record["_acl"]["owners"], json!(["alice"]));
+        assert_eq!(RecordAcl::from_record(&record).unwrap(), Some(acl.clone()));
+
+        assert!(acl.allows("alice", AclAccess::Own));
+        assert!(acl.allows("carol", AclAccess::Read));
+        assert!(acl.allows("carol", AclAccess::Write));
+        assert!(!acl.allows("carol", AclAccess::Own));
+        assert!(acl.allows("bob", AclAccess::Read));
+        assert!(!acl.allows("bob", AclAccess::Write));
+        assert!(!acl.allows("mallory", AclAccess::Read));
+
+        record.insert("_acl".to_string(), json!("everyone"));
+        assert!(RecordAcl::from_record(&record).is_err());
+    }
+
     #[test]
     fn test_query_options_serialization() {
         let options = QueryOptions {
-            sort_by: Some(("created_at".to_string(), Direction::Desc)),
+            sort_by: Some(("created_at".into(), Direction::Desc)),
             limit: Some(100),
             offset: Some(20),
+            distinct_on: Some("email".into()),
+            max_response_bytes: Some(crate::types::ByteSize::from_mib(1)),
+            visible_as: Some(crate::types::AclAccess::Read),
+            stream: true,
+            throttle: Some(crate::types::ScanThrottle { max_records_per_second: Some(1_000), pause_between_batches: None }),
+            ..Default::default()
         };
         
         // Can use bincode for this since it doesn't have serde_json::Value
         test_serialization_bincode(options);
+
+        // Options from clients that predate `stream` still decode.
+        let old: QueryOptions = serde_json::from_str(r#"{"sort_by":null,"limit":10,"offset":null}"#).unwrap();
+        assert_eq!(old, QueryOptions { limit: Some(10), ..Default::default() });
+    }
+
+    #[test]
+    fn test_sort_expr_serialization() {
+        // Sort by `price * quantity`, then by relevance as a separate query.
+        let total = SortExpr::Multiply(
+            Box::new(SortExpr::Field("price".into())),
+            Box::new(SortExpr::Field("quantity".into())),
+        );
+        let options = QueryOptions {
+            sort_expr: Some((total, Direction::Desc)),
+            limit: Some(10),
+            ..Default::default()
+        };
+        test_serialization_bincode(options);
+
+        let options = QueryOptions {
+            sort_expr: Some((SortExpr::Relevance, Direction::Desc)),
+            ..Default::default()
+        };
+        test_serialization_bincode(options);
     }
 
     #[test]
@@ -159,11 +655,48 @@ mod tests {
         requests.insert("key1".to_string(), ("testdb".to_string(), "users".to_string(), "user_1".to_string()));
         requests.insert("key2".to_string(), ("testdb".to_string(), "products".to_string(), "product_1".to_string()));
         
-        let batch_request = BatchRequest { requests };
+        let mut projections = HashMap::new();
+        projections.insert("key1".to_string(), Projection {
+            fields: vec![
+                "name".into(),
+                ProjectionEntry::Alias { from: "avatar_url".into(), to: "avatar".to_string() },
+            ],
+        });
+        projections.insert("key2".to_string(), Projection {
+            fields: vec![ProjectionEntry::Computed {
+                name: "total".to_string(),
+                expr: Expr::Multiply(
+                    Box::new(Expr::Field("price".into())),
+                    Box::new(Expr::Field("qty".into())),
+                ),
+            }],
+        });
+        
+        let batch_request = BatchRequest { requests, projections };
         // Can use bincode for this since it doesn't have serde_json::Value
         test_serialization_bincode(batch_request);
     }
 
+    #[test]
+    fn test_computed_projection_serialization() {
+        // `display_name = coalesce(nickname, first + " " + last, "anonymous")`
+        let projection = Projection {
+            fields: vec![ProjectionEntry::Computed {
+                name: "display_name".to_string(),
+                expr: Expr::Coalesce(vec![
+                    Expr::Field("nickname".into()),
+                    Expr::Concat(vec![
+                        Expr::Field("first".into()),
+                        Expr::Literal(json!(" ")),
+                        Expr::Field("last".into()),
+                    ]),
+                    Expr::Literal(json!("anonymous")),
+                ]),
+            }],
+        };
+        test_serialization_json(projection);
+    }
+
     #[test]
     fn test_batch_response_serialization() {
         let mut record1 = Record::new();
@@ -183,6 +716,763 @@ mod tests {
         test_serialization_json(batch_response);
     }
 
+    #[test]
+    fn test_corpus_seeds_roundtrip() {
+        // Every seed must be a well-formed message, otherwise the fuzzer
+        // starts from inputs the decoders reject immediately.
+        for request in crate::corpus::requests() {
+            test_serialization_json(request);
+        }
+        for response in crate::corpus::responses() {
+            test_serialization_json(response);
+        }
+        for filter in crate::corpus::filters() {
+            test_serialization_json(filter);
+        }
+
+        // The frame seeds must get past the frame layer to reach the decoder.
+        let root = std::env::temp_dir().join(format!("aether-protocol-corpus-{}", std::process::id()));
+        crate::corpus::write_corpus(&root).expect("Failed to write corpus");
+        for entry in std::fs::read_dir(root.join("decode_frame")).unwrap() {
+            let bytes = std::fs::read(entry.unwrap().path()).unwrap();
+            let result = crate::framing::read_frame::<_, Request>(&mut bytes.as_slice());
+            assert!(!matches!(
+                result,
+                Err(crate::ProtocolError::BadMagic | crate::ProtocolError::ChecksumMismatch { .. } | crate::ProtocolError::Io(_))
+            ));
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_codec_instrument() {
+        use crate::codec::{Codec, Instrument, TrafficDirection};
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<(TrafficDirection, &'static str, usize)>>);
+
+        impl Instrument for Recorder {
+            fn record(&self, direction: TrafficDirection, variant: &'static str, encoded_size: usize, _elapsed: Duration) {
+                self.0.lock().unwrap().push((direction, variant, encoded_size));
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let codec = Codec::with_instrument(recorder.clone());
+
+        let request = Request::GetRecord {
+            db_name: Some("testdb".to_string()),
+            collection: "users".to_string(),
+            record_id: "user123".to_string(),
+            with_version: false,
+        };
+        let bytes = codec.encode_request(&request).expect("Failed to encode");
+        assert_eq!(codec.decode_request(&bytes).expect("Failed to decode"), request);
+
+        let bytes = codec.encode_response(&Response::RecordCount(7)).expect("Failed to encode");
+        assert_eq!(codec.decode_response(&bytes).expect("Failed to decode"), Response::RecordCount(7));
+
+        // Failed decodes are not reported.
+        assert!(codec.decode_request(&[0xff; 3]).is_err());
+
+        let events = recorder.0.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].0, TrafficDirection::Encode);
+        assert_eq!(events[0].1, "GetRecord");
+        assert_eq!(events[1].0, TrafficDirection::Decode);
+        assert_eq!(events[0].2, events[1].2);
+        assert_eq!(events[3].1, "RecordCount");
+    }
+
+    #[test]
+    fn test_protocol_config() {
+        use crate::config::ProtocolConfig;
+        use crate::error::ProtocolError;
+
+        let config = ProtocolConfig::default();
+        let deep = (0..config.max_filter_depth).fold(Filter::Exists { field: "a".into() }, |filter, _| !filter);
+        assert_eq!(deep.depth(), config.max_filter_depth + 1);
+        assert!(matches!(
+            config.check_filter(&deep),
+            Err(ProtocolError::LimitExceeded { limit: "max_filter_depth", .. })
+        ));
+        assert!(config.check_filter(&Filter::And(vec![!Filter::Exists { field: "a".into() }])).is_ok());
+        assert_eq!(Filter::And(vec![]).depth(), 1);
+        assert!(config.check_batch_len(config.max_batch_size).is_ok());
+        assert!(config.check_batch_len(config.max_batch_size + 1).is_err());
+
+        // Deployments can override a subset of the limits.
+        let config: ProtocolConfig = serde_json::from_str(r#"{"max_batch_size": 10}"#).unwrap();
+        assert_eq!(config.max_batch_size, 10);
+        assert_eq!(config.max_frame_size, ProtocolConfig::default().max_frame_size);
+        test_serialization_json(config);
+    }
+
+    #[test]
+    fn test_unit_newtypes() {
+        use crate::types::{ByteSize, DurationMs};
+        use std::time::Duration;
+
+        assert_eq!(DurationMs::from_secs(90), DurationMs(90_000));
+        assert_eq!(DurationMs::from(Duration::from_micros(2_500_999)), DurationMs(2_500));
+        assert_eq!(Duration::from(DurationMs(1_500)), Duration::from_millis(1_500));
+        assert_eq!(DurationMs::from(Duration::MAX), DurationMs(u64::MAX));
+        assert_eq!(ByteSize::from_mib(16), ByteSize(16 * 1024 * 1024));
+        assert_eq!(ByteSize(512).to_string(), "512 B");
+        assert_eq!(ByteSize(1536).to_string(), "1.5 KiB");
+        assert_eq!(ByteSize::from_mib(16).to_string(), "16.0 MiB");
+        assert_eq!(ByteSize(u64::MAX).to_string(), "16.0 EiB");
+
+        // Both serialize exactly like the bare integers they replace.
+        assert_eq!(serde_json::to_string(&DurationMs(250)).unwrap(), "250");
+        assert_eq!(bincode::serialize(&ByteSize(7)).unwrap(), bincode::serialize(&7u64).unwrap());
+        assert_eq!(bincode::serialize(&Some(ByteSize(7))).unwrap(), bincode::serialize(&Some(7usize)).unwrap());
+    }
+
+    #[test]
+    fn test_protocol_version_compatibility() {
+        use crate::types::ProtocolVersion;
+
+        let v2_0 = ProtocolVersion { major: 2, minor: 0 };
+        let v2_3 = ProtocolVersion { major: 2, minor: 3 };
+        let v1_9 = ProtocolVersion { major: 1, minor: 9 };
+        assert!(v2_0.is_compatible_with(&v2_3));
+        assert!(v2_3.is_compatible_with(&v2_0));
+        assert!(!v2_0.is_compatible_with(&v1_9));
+        assert!(v1_9 < v2_0);
+        test_serialization_bincode(v2_3);
+
+        use crate::version::{is_compatible, Capability, Compatibility, PROTOCOL_VERSION};
+        assert_eq!(is_compatible(v2_3, v2_0), Compatibility::Compatible { common: v2_0 });
+        assert_eq!(is_compatible(v2_0, v2_3), Compatibility::Compatible { common: v2_0 });
+        assert_eq!(is_compatible(v1_9, v2_0), Compatibility::Incompatible);
+        assert!(!Compatibility::Incompatible.supports(Capability::WriteReceipts));
+        assert!(is_compatible(PROTOCOL_VERSION, PROTOCOL_VERSION).supports(Capability::WriteReceipts));
+        assert!(is_compatible(PROTOCOL_VERSION, PROTOCOL_VERSION).allows(&Request::Flush));
+        assert!(!is_compatible(PROTOCOL_VERSION, PROTOCOL_VERSION).allows(&Request::Unknown));
+        assert!(!Compatibility::Incompatible.allows(&Request::Flush));
+        assert_eq!(Request::GetStats.since(), Some(crate::types::ProtocolVersion { major: 1, minor: 0 }));
+        for capability in Capability::ALL {
+            assert!(PROTOCOL_VERSION.supports(capability));
+            assert_eq!(Capability::from_name(capability.name()), Some(capability));
+        }
+        assert_eq!(Capability::from_name(crate::Compression::Lz4.feature_name().unwrap()), Some(Capability::Lz4Compression));
+        assert_eq!(Capability::from_name(crate::Compression::Zstd.feature_name().unwrap()), Some(Capability::ZstdCompression));
+    }
+
+    #[test]
+    fn test_helpers() {
+        let request = crate::get("shop", "users", "user123");
+        assert_eq!(
+            request,
+            Request::GetRecord {
+                db_name: Some("shop".to_string()),
+                collection: "users".to_string(),
+                record_id: "user123".to_string(),
+                with_version: false,
+            }
+        );
+
+        let request = crate::find("shop", "users", Filter::field("age").gt(21));
+        assert!(matches!(request, Request::FindRecords { options: None, .. }));
+        let bytes = crate::encode_request(&request, crate::format::WireFormat::Json).expect("Failed to encode");
+        assert_eq!(crate::framing::read_frame::<_, Request>(&mut bytes.as_slice()).expect("Failed to decode"), request);
+        let bytes = crate::encode_request(&Request::Flush, crate::format::WireFormat::Bincode).expect("Failed to encode");
+        assert_eq!(crate::framing::read_frame::<_, Request>(&mut bytes.as_slice()).expect("Failed to decode"), Request::Flush);
+        assert!(matches!(crate::insert("shop", "users", Record::new()), Request::InsertRecord { ttl: None, .. }));
+        assert!(matches!(crate::delete("shop", "users", "user123"), Request::DeleteRecord { cascade: false, .. }));
+
+        let response = Response::Record(Some(HashMap::from([("age".to_string(), json!(42)), ("tags".to_string(), json!(["a", null]))])));
+        let mut bytes = Vec::new();
+        let options = crate::framing::FrameOptions { format: crate::format::WireFormat::Json, ..Default::default() };
+        crate::framing::write_frame_with(&mut bytes, &response, options).expect("Failed to encode");
+        assert_eq!(crate::parse_response(&bytes).expect("Failed to parse"), response);
+        assert!(crate::parse_response(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn test_record_accessors() {
+        use crate::record::RecordExt;
+        use crate::ProtocolError;
+
+        let record: Record = HashMap::from([
+            ("name".to_string(), json!("Ada")),
+            ("age".to_string(), json!(36)),
+            ("score".to_string(), json!(9.5)),
+            ("active".to_string(), json!(true)),
+            ("address".to_string(), json!({ "city": "London", "tags": ["home", "billing"] })),
+        ]);
+
+        assert_eq!(record.get_str("name").unwrap(), "Ada");
+        assert_eq!(record.get_i64("age").unwrap(), 36);
+        assert_eq!(record.get_f64("age").unwrap(), 36.0);
+        assert_eq!(record.get_f64("score").unwrap(), 9.5);
+        assert!(record.get_bool("active").unwrap());
+        assert_eq!(record.get_array("address.tags").unwrap().len(), 2);
+        assert_eq!(record.get_str("address.tags[1]").unwrap(), "billing");
+
+        assert!(matches!(record.get_str("address.zip"), Err(ProtocolError::MissingField(path)) if path == "address.zip"));
+        assert!(matches!(
+            record.get_i64("score"),
+            Err(ProtocolError::FieldType { path, expected: "integer", found: "float" }) if path == "score"
+        ));
+        assert!(matches!(record.get_nested("a..b"), Err(ProtocolError::InvalidFieldPath(_))));
+    }
+
+    #[test]
+    fn test_unknown_variants() {
+        use crate::codec::Codec;
+        use crate::{ProtocolError, RequestEnvelope};
+
+        // Forge a message from a newer peer with a variant index this build
+        // does not have.
+        let mut future = 10_000u32.to_le_bytes().to_vec();
+        future.extend_from_slice(&[0xff; 16]);
+
+        let strict = Codec::new();
+        assert!(matches!(strict.decode_request(&future), Err(ProtocolError::Bincode(_))));
+
+        let lenient = Codec::new().with_unknown_variants(true);
+        assert_eq!(lenient.decode_request(&future).unwrap(), Request::Unknown);
+
+        let mut enveloped = 9u64.to_le_bytes().to_vec();
+        enveloped.extend_from_slice(&future);
+        assert_eq!(
+            lenient.decode_request_envelope(&enveloped).unwrap(),
+            RequestEnvelope::new(9, Request::Unknown)
+        );
+
+        assert_eq!(lenient.decode_response(&future).unwrap(), Response::Unknown);
+
+        // Other failures are still errors, and the stand-ins never reach the wire.
+        let known = Codec::new().encode_request(&Request::Cancel { request_id: 1 }).unwrap();
+        assert!(lenient.decode_request(&known[..2]).is_err());
+
+        // So is a bad nested enum inside a known variant.
+        let find = |direction| Request::FindRecords {
+            db_name: None,
+            collection: "users".to_string(),
+            filter: Filter::And(vec![]),
+            options: Some(QueryOptions { sort_by: Some(("age".into(), direction)), ..Default::default() }),
+        };
+        let asc = Codec::new().encode_request(&find(Direction::Asc)).unwrap();
+        let desc = Codec::new().encode_request(&find(Direction::Desc)).unwrap();
+        let mut corrupt = asc.clone();
+        let at = asc.iter().zip(&desc).position(|(a, b)| a != b).unwrap();
+        corrupt[at] = 0xff;
+        assert!(matches!(lenient.decode_request(&corrupt), Err(ProtocolError::Bincode(_))));
+        let json = Codec::new().with_format(crate::format::WireFormat::Json).with_unknown_variants(true);
+        let text = String::from_utf8(json.encode_request(&find(Direction::Asc)).unwrap()).unwrap();
+        for (from, to) in [("\"Asc\"", "\"Sideways\""), ("\"And\"", "\"Nand\"")] {
+            assert!(matches!(json.decode_request(text.replace(from, to).as_bytes()), Err(ProtocolError::Json(_))), "{to}");
+        }
+        assert!(lenient.encode_request(&Request::Unknown).is_err());
+        assert!(lenient.encode_response(&Response::Unknown).is_err());
+    }
+
+    #[test]
+    fn test_codec_hooks() {
+        use crate::codec::{Codec, CodecHooks, Message, TrafficDirection};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Audit(Mutex<Vec<String>>);
+
+        impl CodecHooks for Audit {
+            fn on_message_decoded(&self, message: Message<'_>, bytes: &[u8]) {
+                self.0.lock().unwrap().push(format!("decoded {} ({} bytes)", message.variant_name(), bytes.len()));
+            }
+
+            fn on_frame_error(&self, direction: TrafficDirection, _error: &crate::ProtocolError, bytes: &[u8]) {
+                self.0.lock().unwrap().push(format!("{direction:?} error ({} bytes)", bytes.len()));
+            }
+        }
+
+        let audit = Arc::new(Audit::default());
+        let codec = Codec::new().with_hooks(audit.clone());
+
+        let bytes = codec.encode_response(&Response::RecordCount(7)).expect("Failed to encode");
+        codec.decode_response(&bytes).expect("Failed to decode");
+        assert!(codec.decode_request(&[0xff; 3]).is_err());
+        // A `Value` cannot be decoded from bincode, so this fails after a successful encode.
+        let bytes = codec.encode_response(&Response::ValueSet(vec![json!(1)])).expect("Failed to encode");
+        assert!(codec.decode_response(&bytes).is_err());
+
+        let events = audit.0.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                "decoded RecordCount (12 bytes)".to_string(),
+                "Decode error (3 bytes)".to_string(),
+                format!("Decode error ({} bytes)", bytes.len()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        use crate::codec::Codec;
+        use crate::{RequestEnvelope, ResponseEnvelope};
+
+        let codec = Codec::new();
+        let request = RequestEnvelope::new(u64::MAX, Request::ListDatabases { options: Default::default() });
+        let bytes = codec.encode_request_envelope(&request).expect("Failed to encode");
+        assert_eq!(codec.decode_request_envelope(&bytes).expect("Failed to decode"), request);
+
+        let response = ResponseEnvelope { request_id: 3, body: Response::RecordCount(7) };
+        let bytes = codec.encode_response_envelope(&response).expect("Failed to encode");
+        assert_eq!(codec.decode_response_envelope(&bytes).expect("Failed to decode"), response);
+
+        // Context applies to the whole request, however it is wrapped.
+        test_serialization_json(RequestEnvelope {
+            request_id: 1,
+            body: Request::RunAs {
+                principal: "alice".to_string(),
+                inner: Box::new(Request::GetRecord {
+                    db_name: None,
+                    collection: "users".to_string(),
+                    record_id: "user123".to_string(),
+                    with_version: false,
+                }),
+            },
+            comment: Some("checkout page".to_string()),
+            labels: HashMap::from([("feature".to_string(), "checkout".to_string())]),
+        });
+
+        // Envelopes from clients that send no context still decode.
+        let plain: RequestEnvelope = serde_json::from_str(r#"{"request_id":2,"body":"Flush"}"#).unwrap();
+        assert_eq!(plain, RequestEnvelope::new(2, Request::Flush));
+        test_serialization_json(ResponseEnvelope {
+            request_id: 1,
+            body: Response::Record(Some(Record::from([("name".to_string(), json!("Alice"))]))),
+        });
+    }
+
+    #[test]
+    fn test_framing_roundtrip() {
+        use crate::error::ProtocolError;
+        use crate::framing::{read_frame, write_frame, HEADER_LEN};
+
+        let request = Request::GetRecord {
+            db_name: Some("testdb".to_string()),
+            collection: "users".to_string(),
+            record_id: "user123".to_string(),
+            with_version: false,
+        };
+        let mut stream = Vec::new();
+        write_frame(&mut stream, &request).expect("Failed to write frame");
+        write_frame(&mut stream, &Response::RecordCount(7)).expect("Failed to write frame");
+
+        let mut reader = stream.as_slice();
+        assert_eq!(read_frame::<_, Request>(&mut reader).expect("Failed to read frame"), request);
+        assert_eq!(read_frame::<_, Response>(&mut reader).expect("Failed to read frame"), Response::RecordCount(7));
+        assert!(reader.is_empty());
+
+        let mut bad_magic = stream.clone();
+        bad_magic[0] = b'{';
+        assert!(matches!(read_frame::<_, Request>(&mut bad_magic.as_slice()), Err(ProtocolError::BadMagic)));
+
+        let mut bad_version = stream.clone();
+        bad_version[1] = 0xff;
+        assert!(matches!(read_frame::<_, Request>(&mut bad_version.as_slice()), Err(ProtocolError::UnsupportedVersion(0xff))));
+
+        // A length prefix promising more than the stream holds is an error, not a huge allocation.
+        let mut truncated = stream[..HEADER_LEN].to_vec();
+        truncated[3..HEADER_LEN].copy_from_slice(&1_000u32.to_le_bytes());
+        assert!(matches!(read_frame::<_, Request>(&mut truncated.as_slice()), Err(ProtocolError::Io(_))));
+        truncated[3..HEADER_LEN].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_frame::<_, Request>(&mut truncated.as_slice()), Err(ProtocolError::FrameTooLarge { .. })));
+
+        let mut unknown_compression = stream.clone();
+        unknown_compression[2] = 0x0f;
+        assert!(matches!(
+            read_frame::<_, Request>(&mut unknown_compression.as_slice()),
+            Err(ProtocolError::UnsupportedCompression(0x0f))
+        ));
+    }
+
+    #[test]
+    fn test_framing_continuation() {
+        use crate::config::ProtocolConfig;
+        use crate::error::ProtocolError;
+        use crate::framing::{read_frame_with, write_frame_with, FrameOptions, HEADER_LEN};
+        use crate::types::ByteSize;
+
+        let response = Response::RecordIdSet((0..100).map(|i| format!("record-{i:04}")).collect());
+        let options = FrameOptions { max_frame_len: 64, checksum: true, ..Default::default() };
+        let mut stream = Vec::new();
+        write_frame_with(&mut stream, &response, options).expect("Failed to write frame");
+        write_frame_with(&mut stream, &Response::Success, options).expect("Failed to write frame");
+
+        let config = ProtocolConfig { max_frame_size: ByteSize(64), ..Default::default() };
+        let mut reader = stream.as_slice();
+        assert_eq!(read_frame_with::<_, Response>(&mut reader, &config).expect("Failed to read frame"), response);
+        assert_eq!(read_frame_with::<_, Response>(&mut reader, &config).expect("Failed to read frame"), Response::Success);
+        assert!(reader.is_empty());
+
+        // The message limit applies to the sum of the continuation frames.
+        let config = ProtocolConfig { max_frame_size: ByteSize(64), max_message_size: ByteSize(256), ..Default::default() };
+        assert!(matches!(
+            read_frame_with::<_, Response>(&mut stream.as_slice(), &config),
+            Err(ProtocolError::LimitExceeded { limit: "max_message_size", .. })
+        ));
+
+        // Each frame is checked against the frame limit before its payload is read.
+        let config = ProtocolConfig { max_frame_size: ByteSize(32), ..Default::default() };
+        assert!(matches!(
+            read_frame_with::<_, Response>(&mut stream.as_slice(), &config),
+            Err(ProtocolError::FrameTooLarge { len: 64, max: 32 })
+        ));
+
+        // A stream cut between continuation frames is an i/o error, not a short message.
+        let first_frame = HEADER_LEN + 64 + 4;
+        assert!(matches!(
+            read_frame_with::<_, Response>(&mut &stream[..first_frame], &ProtocolConfig::default()),
+            Err(ProtocolError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_framing_checksum() {
+        use crate::error::ProtocolError;
+        use crate::framing::{crc32, read_frame, write_frame_with, FrameOptions, CHECKSUM_LEN, HEADER_LEN};
+
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+
+        let options = FrameOptions { checksum: true, ..Default::default() };
+        let mut stream = Vec::new();
+        write_frame_with(&mut stream, &Response::RecordCount(7), options).expect("Failed to write frame");
+        write_frame_with(&mut stream, &Response::Success, options).expect("Failed to write frame");
+        let mut reader = stream.as_slice();
+        assert_eq!(read_frame::<_, Response>(&mut reader).expect("Failed to read frame"), Response::RecordCount(7));
+        assert_eq!(read_frame::<_, Response>(&mut reader).expect("Failed to read frame"), Response::Success);
+
+        // A flipped payload bit is reported as corruption, not as a decode error.
+        let mut corrupt = stream.clone();
+        corrupt[HEADER_LEN] ^= 0x01;
+        assert!(matches!(
+            read_frame::<_, Response>(&mut corrupt.as_slice()),
+            Err(ProtocolError::ChecksumMismatch { .. })
+        ));
+
+        // So is a frame whose length prefix was cut short by a proxy.
+        let mut shortened = Vec::new();
+        write_frame_with(&mut shortened, &Response::RecordCount(7), options).expect("Failed to write frame");
+        shortened[3] -= 1;
+        shortened.remove(shortened.len() - CHECKSUM_LEN - 1);
+        assert!(matches!(
+            read_frame::<_, Response>(&mut shortened.as_slice()),
+            Err(ProtocolError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_framing_compression() {
+        use crate::framing::{read_frame, write_frame_with, Compression, FrameOptions};
+
+        let response = Response::RecordIdSet((0..1_000).map(|i| format!("user-{i:06}")).collect());
+        for compression in Compression::supported() {
+            let mut stream = Vec::new();
+            let options = FrameOptions { compression, ..Default::default() };
+            write_frame_with(&mut stream, &response, options).expect("Failed to write frame");
+            if compression != Compression::None {
+                let plain = bincode::serialize(&response).expect("Failed to serialize");
+                assert!(stream.len() < plain.len(), "{compression:?} did not shrink the payload");
+            }
+            assert_eq!(read_frame::<_, Response>(&mut stream.as_slice()).expect("Failed to read frame"), response);
+        }
+
+        // An unlimited message size must not overflow the decompression limit.
+        let unlimited = crate::ProtocolConfig { max_message_size: crate::types::ByteSize(u64::MAX), ..Default::default() };
+        for compression in Compression::supported() {
+            let mut stream = Vec::new();
+            write_frame_with(&mut stream, &response, FrameOptions { compression, ..Default::default() }).expect("Failed to write frame");
+            let decoded: Response = crate::framing::read_frame_with(&mut stream.as_slice(), &unlimited).expect("Failed to read frame");
+            assert_eq!(decoded, response);
+        }
+
+        // Without the matching feature, compressing is an error rather than a silently plain frame.
+        if !cfg!(feature = "zstd") {
+            let options = FrameOptions { compression: Compression::Zstd, ..Default::default() };
+            assert!(write_frame_with(&mut Vec::new(), &Response::Success, options).is_err());
+        }
+    }
+
+    #[test]
+    fn test_wire_formats() {
+        use crate::codec::Codec;
+        use crate::format::WireFormat;
+        use crate::framing::{read_frame, write_frame_with, FrameOptions};
+        use crate::version::Capability;
+        use crate::{ProtocolError, RequestEnvelope};
+
+        // Records hold JSON values, which bincode cannot decode, so the
+        // self-describing formats get a request with one.
+        for format in WireFormat::supported() {
+            let request = match format {
+                WireFormat::Bincode => Request::Cancel { request_id: 7 },
+                _ => Request::InsertRecord {
+                    db_name: Some("shop".to_string()),
+                    collection: "users".to_string(),
+                    data: HashMap::from([("name".to_string(), json!("Alice")), ("tags".to_string(), json!(["a", 1, null]))]),
+                    ttl: None,
+                },
+            };
+            let codec = Codec::new().with_format(format);
+            let bytes = codec.encode_request(&request).expect("Failed to encode");
+            assert_eq!(codec.decode_request(&bytes).expect("Failed to decode"), request);
+
+            // Frames record their format, so the reader needs no configuration.
+            let mut stream = Vec::new();
+            write_frame_with(&mut stream, &request, FrameOptions { format, ..Default::default() }).expect("Failed to write frame");
+            assert_eq!(read_frame::<_, Request>(&mut stream.as_slice()).expect("Failed to read frame"), request);
+
+            if let Some(name) = format.feature_name() {
+                assert!(Capability::from_name(name).is_some());
+
+                // Lenient codecs read the outer variant name in every self-describing format.
+                let lenient = codec.with_unknown_variants(true);
+                let future = format.encode(&json!({ "request_id": 5, "body": { "Teleport": { "to": "mars" } } })).unwrap();
+                assert_eq!(lenient.decode_request_envelope(&future).unwrap(), RequestEnvelope::new(5, Request::Unknown));
+                assert_eq!(lenient.decode_response(&format.encode(&"Teleported").unwrap()).unwrap(), Response::Unknown);
+            }
+        }
+
+        let json = Codec::new().with_format(WireFormat::Json);
+        assert_eq!(json.encode_response(&Response::RecordCount(3)).unwrap(), br#"{"RecordCount":3}"#);
+        let future = br#"{"request_id":5,"body":{"Teleport":{"to":"mars"}}}"#;
+        assert!(json.decode_request_envelope(future).is_err());
+        assert_eq!(
+            json.with_unknown_variants(true).decode_request_envelope(future).unwrap(),
+            RequestEnvelope::new(5, Request::Unknown)
+        );
+
+        if !cfg!(feature = "cbor") {
+            let cbor = Codec::new().with_format(WireFormat::Cbor);
+            assert!(matches!(cbor.encode_response(&Response::Success), Err(ProtocolError::UnsupportedFormat(3))));
+        }
+    }
+
+    #[test]
+    fn test_response_views() {
+        use crate::view::ResponseRef;
+
+        let response = Response::RecordSet(RecordSet {
+            records: vec![
+                HashMap::from([("name".to_string(), json!("Alice")), ("age".to_string(), json!(30))]),
+                HashMap::from([("name".to_string(), json!("B\"ob")), ("tags".to_string(), json!(["x"]))]),
+            ],
+            truncated: false,
+            continuation: None,
+        });
+        let json = serde_json::to_string(&response).unwrap();
+        let view = ResponseRef::parse(&json).unwrap();
+        assert_eq!(view.variant_name(), response.variant_name());
+
+        let records = view.records().unwrap().expect("RecordSet carries records");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get_as::<&str>("name").unwrap(), Some("Alice"));
+        assert_eq!(records[0].get_as::<u64>("age").unwrap(), Some(30));
+        assert_eq!(records[1].get("tags").unwrap().map(|raw| raw.get()), Some(r#"["x"]"#));
+        assert!(records[1].get("age").unwrap().is_none());
+        // Escaped strings cannot be borrowed, but can still be decoded owned.
+        assert_eq!(records[1].get_as::<String>("name").unwrap().as_deref(), Some("B\"ob"));
+        assert_eq!(records[0].to_record().unwrap(), HashMap::from([("name".to_string(), json!("Alice")), ("age".to_string(), json!(30))]));
+        assert_eq!(view.decode().unwrap(), response);
+
+        let chunk = serde_json::to_string(&Response::RecordSetChunk { records: vec![], seq: 4 }).unwrap();
+        assert_eq!(ResponseRef::parse(&chunk).unwrap().records().unwrap().map(|records| records.len()), Some(0));
+        let missing = serde_json::to_string(&Response::Record(None)).unwrap();
+        assert_eq!(ResponseRef::parse(&missing).unwrap().records().unwrap().map(|records| records.len()), Some(0));
+
+        let success = serde_json::to_string(&Response::Success).unwrap();
+        let view = ResponseRef::parse(&success).unwrap();
+        assert_eq!(view.variant_name(), "Success");
+        assert!(view.body().is_none());
+        assert!(view.records().unwrap().is_none());
+        assert!(ResponseRef::parse(r#"{"RecordCount":1,"Success":null}"#).is_err());
+    }
+
+    #[test]
+    fn test_stream_transport() {
+        use crate::framing::FrameOptions;
+        use crate::transport::{StreamTransport, Transport};
+        use std::io::Cursor;
+
+        let options = FrameOptions { checksum: true, max_frame_len: 8, ..Default::default() };
+        let mut transport = StreamTransport::new(Cursor::new(Vec::new())).with_options(options);
+        transport.write_frame(&Request::Cancel { request_id: 1 }).expect("Failed to write frame");
+        transport.write_frame(&Response::RecordIdSet(vec!["a".to_string(); 4])).expect("Failed to write frame");
+
+        // Transports can be chosen at runtime behind a trait object.
+        let mut transport: Box<dyn Transport> = Box::new(StreamTransport::new(Cursor::new(transport.into_inner().into_inner())));
+        assert_eq!(transport.recv_request().unwrap(), Request::Cancel { request_id: 1 });
+        assert_eq!(transport.recv_response().unwrap(), Response::RecordIdSet(vec!["a".to_string(); 4]));
+        assert!(transport.recv_response().is_err());
+    }
+
+    #[cfg(feature = "memory")]
+    #[test]
+    fn test_memory_transport() {
+        use crate::transport::{MemoryTransport, Transport};
+
+        let (mut client, mut server) = MemoryTransport::pair();
+        client.write_frame(&Request::GetStats).unwrap();
+        assert_eq!(server.read_frame::<Request>().unwrap(), Request::GetStats);
+        server.write_frame(&Response::Success).unwrap();
+        assert_eq!(client.read_frame::<Response>().unwrap(), Response::Success);
+
+        let mut client: Box<dyn Transport> = Box::new(client);
+        client.send_request(&Request::Flush).unwrap();
+        assert_eq!(server.recv_request().unwrap(), Request::Flush);
+        server.send_response(&Response::Success).unwrap();
+        assert_eq!(client.recv_response().unwrap(), Response::Success);
+
+        drop(server);
+        assert!(client.recv_response().is_err());
+        assert!(client.send_request(&Request::GetStats).is_err());
+    }
+
+    #[cfg(feature = "tcp")]
+    #[test]
+    fn test_tcp_transport() {
+        use crate::transport::{StreamTransport, TcpTransport, Transport};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut server = StreamTransport::new(listener.accept().unwrap().0);
+            assert_eq!(server.read_frame::<Request>().unwrap(), Request::Flush);
+            server.write_frame(&Response::Success).unwrap();
+        });
+
+        let mut client = TcpTransport::connect(addr).unwrap();
+        client.write_frame(&Request::Flush).unwrap();
+        assert_eq!(client.read_frame::<Response>().unwrap(), Response::Success);
+        server.join().unwrap();
+    }
+
+    #[cfg(all(unix, feature = "unix"))]
+    #[test]
+    fn test_unix_transport() {
+        use crate::transport::{StreamTransport, Transport, UnixTransport};
+        use std::os::unix::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("aether-protocol-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            let mut server = StreamTransport::new(listener.accept().unwrap().0);
+            assert_eq!(server.read_frame::<Request>().unwrap(), Request::Flush);
+            server.write_frame(&Response::Success).unwrap();
+        });
+
+        let mut client = UnixTransport::connect(&path).unwrap();
+        client.write_frame(&Request::Flush).unwrap();
+        assert_eq!(client.read_frame::<Response>().unwrap(), Response::Success);
+        server.join().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_tokio_codec() {
+        use crate::error::ProtocolError;
+        use crate::tokio_codec::{AetherCodec, AetherServerCodec};
+        use bytes::BytesMut;
+        use tokio_util::codec::{Decoder, Encoder};
+
+        let request = Request::ListDatabases { options: Default::default() };
+        let mut client = AetherCodec::new();
+        let mut server = AetherServerCodec::new();
+
+        let mut wire = BytesMut::new();
+        client.encode(request, &mut wire).expect("Failed to encode");
+        // A partial frame waits for more bytes without consuming anything.
+        let mut partial = BytesMut::from(&wire[..wire.len() - 1]);
+        assert_eq!(server.decode(&mut partial).expect("Failed to decode"), None);
+        assert_eq!(partial.len(), wire.len() - 1);
+        assert_eq!(
+            server.decode(&mut wire).expect("Failed to decode"),
+            Some(Request::ListDatabases { options: Default::default() })
+        );
+        assert!(wire.is_empty());
+
+        server.encode(Response::RecordCount(7), &mut wire).expect("Failed to encode");
+        assert_eq!(client.decode(&mut wire).expect("Failed to decode"), Some(Response::RecordCount(7)));
+
+        let mut checksummed = AetherCodec::new().with_checksum(true);
+        checksummed.encode(Request::GetStats, &mut wire).expect("Failed to encode");
+        let last = wire.len() - 1;
+        wire[last] ^= 0xff;
+        assert!(matches!(server.decode(&mut wire), Err(ProtocolError::ChecksumMismatch { .. })));
+        assert!(wire.is_empty());
+
+        // Messages larger than the frame limit travel as continuation frames.
+        let big = Request::CreateDatabase { db_name: "x".repeat(200) };
+        let mut chunked = AetherCodec::new().with_max_frame_len(16);
+        chunked.encode(Request::CreateDatabase { db_name: "x".repeat(200) }, &mut wire).expect("Failed to encode");
+        let mut small_frames = AetherServerCodec::new().with_max_frame_len(16);
+        let mut partial = wire.split_to(wire.len() - 1);
+        assert_eq!(small_frames.decode(&mut partial).expect("Failed to decode"), None);
+        partial.unsplit(wire);
+        let mut wire = partial;
+        assert_eq!(small_frames.decode(&mut wire).expect("Failed to decode"), Some(big));
+        assert!(wire.is_empty());
+
+        let mut limited = AetherServerCodec::new().with_max_frame_len(4);
+        client.encode(Request::GetStats, &mut wire).expect("Failed to encode");
+        client.encode(Request::CreateDatabase { db_name: "x".repeat(64) }, &mut wire).expect("Failed to encode");
+        assert_eq!(limited.decode(&mut wire).expect("Failed to decode"), Some(Request::GetStats));
+        assert!(matches!(limited.decode(&mut wire), Err(ProtocolError::FrameTooLarge { max: 4, .. })));
+    }
+
+    #[test]
+    fn test_journal_roundtrip() {
+        use crate::journal::{JournalEntry, JournalReader, JournalWriter};
+
+        let entries = vec![
+            JournalEntry::now(1, Request::ListDatabases { options: Default::default() }),
+            JournalEntry {
+                request_id: 2,
+                timestamp_micros: 1_700_000_000_000_000,
+                request: Request::CreateRecord {
+                    db_name: Some("testdb".to_string()),
+                    collection: "users".to_string(),
+                    record_id: "user123".to_string(),
+                    data: {
+                        let mut record = Record::new();
+                        record.insert("name".to_string(), json!("Alice"));
+                        record
+                    },
+                    ttl: None,
+                },
+            },
+        ];
+
+        let mut writer = JournalWriter::new(Vec::new()).expect("Failed to write header");
+        for entry in &entries {
+            writer.append(entry).expect("Failed to append entry");
+        }
+        let bytes = writer.into_inner();
+
+        let reader = JournalReader::new(bytes.as_slice()).expect("Failed to read header");
+        let replayed: Vec<JournalEntry> = reader.collect::<Result<_, _>>().expect("Failed to read entries");
+        assert_eq!(entries, replayed);
+
+        // A journal cut off mid-entry is an error, not a clean end.
+        let truncated = &bytes[..bytes.len() - 1];
+        let mut reader = JournalReader::new(truncated).expect("Failed to read header");
+        assert!(reader.next_entry().unwrap().is_some());
+        assert!(reader.next_entry().is_err());
+
+        assert!(matches!(JournalReader::new(&b"NOTAJOURNAL"[..]), Err(crate::ProtocolError::BadMagic)));
+    }
+
     #[test]
     fn test_request_serialization() {
         // Test all Request variants
@@ -190,11 +1480,40 @@ mod tests {
             // Database Management
             Request::CreateDatabase { db_name: "testdb".to_string() },
             Request::DropDatabase { db_name: "testdb".to_string() },
-            Request::ListDatabases,
+            Request::ListDatabases { options: Default::default() },
             
             // Collection Management
-            Request::ListCollections,
-            Request::CreateCollection { db_name: "users".to_string(), collection_name: "users".to_string() },
+            Request::ListCollections {
+                options: crate::types::ListOptions {
+                    name_prefix: Some("events_2024".to_string()),
+                    limit: Some(100),
+                    page_token: Some("p2".to_string()),
+                },
+            },
+            Request::CreateCollection {
+                db_name: "users".to_string(),
+                collection_name: "users".to_string(),
+                options: Default::default(),
+            },
+            Request::CreateCollection {
+                db_name: "reports".to_string(),
+                collection_name: "staging".to_string(),
+                options: crate::types::CollectionOptions {
+                    lifetime: crate::types::CollectionLifetime::UntilDisconnect,
+                    ..Default::default()
+                },
+            },
+            Request::CreateCollection {
+                db_name: "metrics".to_string(),
+                collection_name: "cpu".to_string(),
+                options: crate::types::CollectionOptions {
+                    time_series: Some(crate::types::TimeSeriesOptions {
+                        time_field: "ts".to_string(),
+                        granularity: crate::types::Granularity::Seconds,
+                    }),
+                    ..Default::default()
+                },
+            },
             Request::DropCollection { db_name: "users".to_string(), collection_name: "users".to_string() },
             Request::GetStats,
             Request::Flush,
@@ -203,21 +1522,32 @@ mod tests {
             Request::CreateIndex {
                 db_name: "users".to_string(),
                 collection: "users".to_string(),
-                field_name: "email".to_string(),
+                field_name: "email".into(),
+                kind: crate::types::IndexKind::BTree,
+            },
+            Request::CreateIndex {
+                db_name: "docs".to_string(),
+                collection: "chunks".to_string(),
+                field_name: "embedding".into(),
+                kind: crate::types::IndexKind::Vector {
+                    dimensions: 768,
+                    metric: crate::types::DistanceMetric::Cosine,
+                },
             },
             Request::DropIndex {
                 db_name: "users".to_string(),
                 collection: "users".to_string(),
-                field_name: "email".to_string(),
+                field_name: "email".into(),
             },
             Request::ListIndexes {
                 db_name: "users".to_string(),
                 collection: "users".to_string(),
+                options: Default::default(),
             },
             
             // CRUD Operations
             Request::CreateRecord {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 record_id: "user123".to_string(),
                 data: {
@@ -226,9 +1556,10 @@ mod tests {
                     record.insert("email".to_string(), json!("alice@example.com"));
                     record
                 },
+                ttl: None,
             },
             Request::UpdateRecord {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 record_id: "user123".to_string(),
                 data: {
@@ -236,9 +1567,11 @@ mod tests {
                     record.insert("active".to_string(), json!(false));
                     record
                 },
+                expected_version: Some(3),
+                return_document: Some(crate::types::ReturnDocument::After),
             },
             Request::UpsertRecord {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 record_id: "user123".to_string(),
                 data: {
@@ -247,50 +1580,59 @@ mod tests {
                     record.insert("email".to_string(), json!("updated@example.com"));
                     record
                 },
+                expected_version: None,
+                ttl: Some(crate::types::DurationMs::from_secs(3_600)),
+                return_document: None,
             },
             Request::GetRecord {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 record_id: "user123".to_string(),
+                with_version: true,
             },
             Request::DeleteRecord {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 record_id: "user123".to_string(),
                 cascade: true,
+                expected_version: None,
+                return_document: Some(crate::types::ReturnDocument::Before),
             },
             Request::GetLastInsertId,
             
             // Querying & Relational
             Request::FindRecords {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 filter: crate::types::Filter::And(vec![
                     crate::types::Filter::Equals {
-                        field: "active".to_string(),
+                        field: "active".into(),
                         value: json!(true),
+                        options: Default::default(),
                     },
                     crate::types::Filter::GreaterThan {
-                        field: "age".to_string(),
-                        value: 21.0,
+                        field: "age".into(),
+                        value: json!(21.0),
                     },
                 ]),
                 options: Some(crate::types::QueryOptions {
-                    sort_by: Some(("created_at".to_string(), crate::types::Direction::Desc)),
+                    sort_by: Some(("created_at".into(), crate::types::Direction::Desc)),
                     limit: Some(50),
                     offset: Some(0),
+                    ..Default::default()
                 }),
             },
             Request::CountRecords {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 filter: crate::types::Filter::Equals {
-                    field: "active".to_string(),
+                    field: "active".into(),
                     value: json!(true),
+                    options: Default::default(),
                 },
             },
             Request::GetRecordWithRelated {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 primary_collection: "orders".to_string(),
                 primary_record_id: "order123".to_string(),
                 relation_key_field: "user_id".to_string(),
@@ -300,20 +1642,426 @@ mod tests {
                 let mut requests = HashMap::new();
                 requests.insert("key1".to_string(), ("testdb".to_string(), "users".to_string(), "user123".to_string()));
                 requests.insert("key2".to_string(), ("testdb".to_string(), "products".to_string(), "product456".to_string()));
-                crate::types::BatchRequest { requests }
+                crate::types::BatchRequest { requests, ..Default::default() }
             }),
             Request::Search {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 query: "John Doe".to_string(),
                 field: Some("name".to_string()),
             },
             Request::Search {
-                db_name: "users".to_string(),
+                db_name: Some("users".to_string()),
                 collection: "users".to_string(),
                 query: "John Doe".to_string(),
                 field: None, // The field is absent
             },
+            Request::FindRecordsMulti {
+                db_name: Some("logs".to_string()),
+                collections: vec!["events_2024_01_01".to_string(), "events_2024_01_02".to_string()],
+                filter: crate::types::Filter::Equals {
+                    field: "level".into(),
+                    value: json!("error"),
+                    options: Default::default(),
+                },
+                options: None,
+            },
+            Request::DescribeCollection {
+                db_name: None,
+                collection: "users".to_string(),
+                sample_size: 1000,
+            },
+            Request::GetServerTime,
+            Request::SetConnectionOption {
+                key: "default_db".to_string(),
+                value: json!("tenant_42"),
+            },
+            Request::GetConnectionOptions,
+            Request::UseDatabase { db_name: "tenant_42".to_string() },
+            Request::GetRecord {
+                db_name: None, // Falls back to the connection's default database
+                collection: "users".to_string(),
+                record_id: "user123".to_string(),
+                with_version: false,
+            },
+            Request::SearchRecords {
+                db_name: Some("blog".to_string()),
+                collection: "posts".to_string(),
+                filter: crate::types::Filter::TextSearch {
+                    field: "body".into(),
+                    query: "rust".to_string(),
+                    options: Default::default(),
+                },
+                options: None,
+            },
+            Request::GetJobStatus { job_id: crate::types::JobId(7) },
+            Request::CancelJob { job_id: crate::types::JobId(7) },
+            Request::VectorSearch {
+                db_name: Some("docs".to_string()),
+                collection: "chunks".to_string(),
+                field: "embedding".into(),
+                query_vector: vec![0.12, -0.5, 0.33],
+                k: 10,
+                filter: Some(crate::types::Filter::Equals {
+                    field: "lang".into(),
+                    value: json!("en"),
+                    options: Default::default(),
+                }),
+            },
+            Request::DistinctValues {
+                db_name: None,
+                collection: "products".to_string(),
+                field: "brand".into(),
+                filter: Some(crate::types::Filter::GreaterThan {
+                    field: "stock".into(),
+                    value: json!(0),
+                }),
+            },
+            Request::FetchMore { cursor: crate::types::Cursor("c-91f3".to_string()) },
+            Request::CloseCursor { cursor: crate::types::Cursor("c-91f3".to_string()) },
+            Request::SetCollectionDefaults {
+                db_name: "logs".to_string(),
+                collection: "events".to_string(),
+                defaults: crate::types::CollectionDefaults {
+                    default_limit: Some(100),
+                    default_sort: Some(("timestamp".into(), Direction::Desc)),
+                    max_scan: Some(1_000_000),
+                },
+            },
+            Request::GetCollectionDefaults {
+                db_name: "logs".to_string(),
+                collection: "events".to_string(),
+            },
+            Request::ExplainQuery {
+                db_name: Some("logs".to_string()),
+                collection: "events".to_string(),
+                filter: Filter::Equals {
+                    field: "level".into(),
+                    value: json!("error"),
+                    options: Default::default(),
+                },
+                options: Some(QueryOptions {
+                    sort_by: Some(("timestamp".into(), Direction::Desc)),
+                    top_k: Some(10),
+                    ..Default::default()
+                }),
+                format: Default::default(),
+            },
+            Request::ExplainQuery {
+                db_name: None,
+                collection: "events".to_string(),
+                filter: Filter::And(vec![]),
+                options: None,
+                format: crate::types::ExplainFormat::Json,
+            },
+            Request::Aggregate {
+                db_name: Some("shop".to_string()),
+                collection: "orders".to_string(),
+                filter: Filter::And(vec![]),
+                group_by: vec!["region".into()],
+                aggregations: vec![
+                    crate::types::Aggregation {
+                        name: "orders".to_string(),
+                        accumulator: crate::types::Accumulator::Count,
+                    },
+                    crate::types::Aggregation {
+                        name: "revenue".to_string(),
+                        accumulator: crate::types::Accumulator::Sum("total".into()),
+                    },
+                ],
+            },
+            Request::Aggregate {
+                db_name: None,
+                collection: "requests".to_string(),
+                filter: Filter::And(vec![]),
+                group_by: vec!["endpoint".into()],
+                aggregations: vec![
+                    crate::types::Aggregation {
+                        name: "p99".to_string(),
+                        accumulator: crate::types::Accumulator::Percentile {
+                            field: "latency_ms".into(),
+                            p: 99.0,
+                        },
+                    },
+                    crate::types::Aggregation {
+                        name: "stddev".to_string(),
+                        accumulator: crate::types::Accumulator::StdDev("latency_ms".into()),
+                    },
+                    crate::types::Aggregation {
+                        name: "median".to_string(),
+                        accumulator: crate::types::Accumulator::Median("latency_ms".into()),
+                    },
+                ],
+            },
+            Request::PlanParallelScan {
+                db_name: None,
+                collection: "events".to_string(),
+                partitions: 8,
+                throttle: Some(crate::types::ScanThrottle {
+                    max_records_per_second: Some(5_000),
+                    pause_between_batches: Some(crate::types::DurationMs(250)),
+                }),
+            },
+            Request::AggregatePipeline {
+                db_name: Some("shop".to_string()),
+                collection: "orders".to_string(),
+                pipeline: crate::types::AggregationPipeline {
+                    stages: vec![
+                        crate::types::PipelineStage::Match(Filter::Equals {
+                            field: "status".into(),
+                            value: json!("paid"),
+                            options: Default::default(),
+                        }),
+                        crate::types::PipelineStage::Project(crate::types::Projection {
+                            fields: vec!["region".into(), "total".into()],
+                        }),
+                        crate::types::PipelineStage::Group {
+                            by: vec!["region".into()],
+                            aggregations: vec![crate::types::Aggregation {
+                                name: "revenue".to_string(),
+                                accumulator: crate::types::Accumulator::Sum("total".into()),
+                            }],
+                        },
+                        crate::types::PipelineStage::Sort(vec![("revenue".into(), Direction::Desc)]),
+                        crate::types::PipelineStage::Limit(5),
+                    ],
+                },
+            },
+            Request::AggregatePipeline {
+                db_name: None,
+                collection: "orders".to_string(),
+                pipeline: crate::types::AggregationPipeline {
+                    stages: vec![
+                        crate::types::PipelineStage::Lookup {
+                            from_collection: "customers".to_string(),
+                            local_field: "customer_id".into(),
+                            foreign_field: "id".into(),
+                            as_field: "customer".to_string(),
+                        },
+                        crate::types::PipelineStage::Unwind(crate::types::FieldPath("customer".to_string())),
+                        crate::types::PipelineStage::Unwind(crate::types::FieldPath("line_items".to_string())),
+                        crate::types::PipelineStage::Group {
+                            by: vec!["customer.region".into()],
+                            aggregations: vec![crate::types::Aggregation {
+                                name: "revenue".to_string(),
+                                accumulator: crate::types::Accumulator::Sum("line_items.total".into()),
+                            }],
+                        },
+                        crate::types::PipelineStage::Limit(10),
+                    ],
+                },
+            },
+            Request::RunAs {
+                principal: "user:alice".to_string(),
+                inner: Box::new(Request::ListCollections { options: Default::default() }),
+            },
+            Request::PatchRecord {
+                db_name: Some("shop".to_string()),
+                collection: "carts".to_string(),
+                record_id: "cart-7".to_string(),
+                ops: vec![
+                    crate::types::UpdateOp::Set { field: "status".into(), value: json!("open") },
+                    crate::types::UpdateOp::Unset { field: "coupon".into() },
+                    crate::types::UpdateOp::Increment { field: "item_count".into(), by: json!(1) },
+                    crate::types::UpdateOp::Multiply { field: "total".into(), by: json!(0.9) },
+                    crate::types::UpdateOp::Push { field: "items".into(), value: json!({ "sku": "A1" }) },
+                    crate::types::UpdateOp::Pull { field: "tags".into(), value: json!("stale") },
+                ],
+                expected_version: None,
+            },
+            Request::ListOperations,
+            Request::KillOperation { op_id: crate::types::OperationId(314) },
+            Request::UpdateRecords {
+                db_name: Some("shop".to_string()),
+                collection: "orders".to_string(),
+                filter: Filter::Equals {
+                    field: "status".into(),
+                    value: json!("pending"),
+                    options: Default::default(),
+                },
+                ops: vec![crate::types::UpdateOp::Set { field: "status".into(), value: json!("expired") }],
+            },
+            Request::ListConnections,
+            Request::KillConnection { conn_id: crate::types::ConnectionId(12) },
+            Request::DeleteRecords {
+                db_name: None,
+                collection: "events".to_string(),
+                filter: Filter::LessThan {
+                    field: "timestamp".into(),
+                    value: json!("2024-01-01T00:00:00Z"),
+                },
+                cascade: false,
+            },
+            Request::FindOneAndUpdate {
+                db_name: Some("queue".to_string()),
+                collection: "jobs".to_string(),
+                filter: Filter::Equals {
+                    field: "state".into(),
+                    value: json!("ready"),
+                    options: Default::default(),
+                },
+                sort_by: Some(("enqueued_at".into(), Direction::Asc)),
+                ops: vec![crate::types::UpdateOp::Set { field: "state".into(), value: json!("claimed") }],
+                return_document: crate::types::ReturnDocument::After,
+            },
+            Request::FindOneAndDelete {
+                db_name: Some("queue".to_string()),
+                collection: "jobs".to_string(),
+                filter: Filter::Exists { field: "done_at".into() },
+                sort_by: None,
+            },
+            Request::ExecuteBatchWrite(crate::types::BatchWriteRequest {
+                db_name: Some("shop".to_string()),
+                operations: vec![
+                    crate::types::WriteOperation::Create {
+                        collection: "orders".to_string(),
+                        record_id: "o-2".to_string(),
+                        data: Record::from([("total".to_string(), json!(12.5))]),
+                        ttl: None,
+                    },
+                    crate::types::WriteOperation::Update {
+                        collection: "orders".to_string(),
+                        record_id: "o-1".to_string(),
+                        data: Record::from([("status".to_string(), json!("shipped"))]),
+                        expected_version: Some(2),
+                    },
+                    crate::types::WriteOperation::Upsert {
+                        collection: "customers".to_string(),
+                        record_id: "c-9".to_string(),
+                        data: Record::new(),
+                        expected_version: None,
+                        ttl: None,
+                    },
+                    crate::types::WriteOperation::Delete {
+                        collection: "carts".to_string(),
+                        record_id: "cart-7".to_string(),
+                        cascade: false,
+                        expected_version: None,
+                    },
+                ],
+                ordered: true,
+            }),
+            Request::SetRecordTtl {
+                db_name: Some("web".to_string()),
+                collection: "sessions".to_string(),
+                record_id: "s-41".to_string(),
+                ttl: Some(crate::types::DurationMs::from_secs(1_800)),
+            },
+            Request::CreateMaterializedView {
+                db_name: "shop".to_string(),
+                name: "revenue_by_region".to_string(),
+                source: "orders".to_string(),
+                pipeline: crate::types::AggregationPipeline {
+                    stages: vec![crate::types::PipelineStage::Group {
+                        by: vec!["region".into()],
+                        aggregations: vec![crate::types::Aggregation {
+                            name: "revenue".to_string(),
+                            accumulator: crate::types::Accumulator::Sum("total".into()),
+                        }],
+                    }],
+                },
+                refresh: crate::types::RefreshPolicy::Interval { every: crate::types::DurationMs::from_secs(300) },
+            },
+            Request::RefreshView {
+                db_name: "shop".to_string(),
+                name: "revenue_by_region".to_string(),
+            },
+            Request::GetViewStats {
+                db_name: "shop".to_string(),
+                name: "revenue_by_region".to_string(),
+            },
+            Request::InsertRecord {
+                db_name: None,
+                collection: "events".to_string(),
+                data: Record::from([("level".to_string(), json!("info"))]),
+                ttl: None,
+            },
+            Request::DownsampleQuery {
+                db_name: Some("metrics".to_string()),
+                collection: "cpu".to_string(),
+                bucket_micros: 60_000_000,
+                aggregations: vec![crate::types::Aggregation {
+                    name: "avg_load".to_string(),
+                    accumulator: crate::types::Accumulator::Avg("load".into()),
+                }],
+                range: crate::types::TimeRange {
+                    start_micros: 1_700_000_000_000_000,
+                    end_micros: 1_700_003_600_000_000,
+                },
+            },
+            Request::ApproxDistinct {
+                db_name: None,
+                collection: "events".to_string(),
+                field: "user_id".into(),
+                filter: None,
+            },
+            Request::BeginTransaction { options: Default::default() },
+            Request::BeginTransaction {
+                options: crate::types::TransactionOptions {
+                    read_only: true,
+                    isolation: crate::types::IsolationLevel::Serializable,
+                    timeout: Some(crate::types::DurationMs(30_000)),
+                },
+            },
+            Request::InTransaction {
+                tx_id: crate::types::TransactionId(7),
+                inner: Box::new(Request::PatchRecord {
+                    db_name: None,
+                    collection: "accounts".to_string(),
+                    record_id: "alice".to_string(),
+                    ops: vec![crate::types::UpdateOp::Increment {
+                        field: "balance".into(),
+                        by: json!(-25),
+                    }],
+                    expected_version: None,
+                }),
+            },
+            Request::CommitTransaction { tx_id: crate::types::TransactionId(7) },
+            Request::RollbackTransaction { tx_id: crate::types::TransactionId(8) },
+            Request::CreateSavepoint { tx_id: crate::types::TransactionId(7), name: "before_fees".to_string() },
+            Request::RollbackToSavepoint { tx_id: crate::types::TransactionId(7), name: "before_fees".to_string() },
+            Request::GetTransactionStatus { tx_id: crate::types::TransactionId(7) },
+            Request::PrepareTransaction { tx_id: crate::types::TransactionId(7) },
+            Request::CommitPrepared { token: crate::types::PreparedToken("p-7f3a".to_string()) },
+            Request::AbortPrepared { token: crate::types::PreparedToken("p-7f3a".to_string()) },
+            Request::AtomicWriteGroup {
+                db_name: Some("bank".to_string()),
+                operations: vec![
+                    crate::types::WriteOperation::Update {
+                        collection: "accounts".to_string(),
+                        record_id: "alice".to_string(),
+                        data: Record::from([("balance".to_string(), json!(75))]),
+                        expected_version: Some(4),
+                    },
+                    crate::types::WriteOperation::Update {
+                        collection: "accounts".to_string(),
+                        record_id: "bob".to_string(),
+                        data: Record::from([("balance".to_string(), json!(125))]),
+                        expected_version: Some(9),
+                    },
+                ],
+            },
+            Request::Hello {
+                client_version: crate::types::ProtocolVersion { major: 2, minor: 1 },
+                supported_features: vec!["compression".to_string(), "envelopes".to_string()],
+                app_name: Some("billing-worker".to_string()),
+            },
+            Request::DryRun {
+                inner: Box::new(Request::DropDatabase { db_name: "staging".to_string() }),
+            },
+            Request::Confirmed {
+                token: crate::types::ConfirmationToken("c0nf1rm-7f3a".to_string()),
+                inner: Box::new(Request::DropCollection {
+                    db_name: "prod".to_string(),
+                    collection_name: "sessions".to_string(),
+                }),
+            },
+            Request::Cancel { request_id: 42 },
+            Request::VerifyCollection {
+                db_name: Some("prod".to_string()),
+                collection: "orders".to_string(),
+                mode: crate::types::VerifyMode::Full,
+            },
         ];
         
         for request in requests {
@@ -330,28 +2078,55 @@ mod tests {
             Response::Error("Invalid request format".to_string()),
             
             // Database Management Responses
-            Response::DatabaseList(vec![
-                "testdb".to_string(),
-                "userdb".to_string(),
-                "analytics".to_string(),
-            ]),
+            Response::DatabaseList(crate::types::Page {
+                items: vec![
+                    "testdb".to_string(),
+                    "userdb".to_string(),
+                    "analytics".to_string(),
+                ],
+                page_info: Default::default(),
+            }),
             Response::DatabaseCreated(true),
             Response::DatabaseDropped(true),
             
             // Collection Management Responses
-            Response::CollectionList(vec![
-                "users".to_string(),
-                "products".to_string(),
-                "orders".to_string(),
-            ]),
+            Response::CollectionList(crate::types::Page {
+                items: vec![
+                    "users".to_string(),
+                    "products".to_string(),
+                    "orders".to_string(),
+                ],
+                page_info: crate::types::PageInfo {
+                    next_page_token: Some("p3".to_string()),
+                    total_count: Some(12_000),
+                },
+            }),
             Response::Stats(crate::types::DbStats {
                 collection_count: 3,
                 record_count: 1500,
             }),
-            Response::IndexList(vec![
-                "email".to_string(),
-                "username".to_string(),
-            ]),
+            Response::IndexList(crate::types::Page {
+                items: vec![
+                    crate::types::IndexInfo {
+                        fields: vec!["email".into()],
+                        unique: true,
+                        kind: crate::types::IndexKind::BTree,
+                        size: crate::types::ByteSize(4_096),
+                        state: crate::types::IndexState::Ready,
+                    },
+                    crate::types::IndexInfo {
+                        fields: vec!["embedding".into()],
+                        unique: false,
+                        kind: crate::types::IndexKind::Vector {
+                            dimensions: 768,
+                            metric: crate::types::DistanceMetric::Cosine,
+                        },
+                        size: crate::types::ByteSize(0),
+                        state: crate::types::IndexState::Building { progress: Some(0.25) },
+                    },
+                ],
+                page_info: Default::default(),
+            }),
             
             // Record & Query Responses
             Response::Record(Some({
@@ -377,6 +2152,7 @@ mod tests {
                         record
                     },
                 ],
+                ..Default::default()
             }),
             Response::RecordCount(42),
             Response::RecordDeleted(true),
@@ -409,6 +2185,218 @@ mod tests {
                 
                 crate::types::BatchResponse { results }
             }),
+            Response::TaggedRecordSet(vec![crate::types::TaggedRecord {
+                collection: "events_2024_01_01".to_string(),
+                record: {
+                    let mut record = Record::new();
+                    record.insert("level".to_string(), json!("error"));
+                    record
+                },
+            }]),
+            Response::CollectionSchema(crate::types::CollectionSchema {
+                sampled_records: 1000,
+                fields: vec![crate::types::FieldInfo {
+                    name: "age".to_string(),
+                    types: vec![crate::types::ValueType::of(&json!(30)), crate::types::ValueType::Null],
+                    occurrence_rate: 0.75,
+                }],
+            }),
+            Response::ServerTime {
+                wall_clock_micros: 1_700_000_000_000_000,
+                monotonic_micros: 86_400_000_000,
+            },
+            Response::ConnectionOptions({
+                let mut options = HashMap::new();
+                options.insert("default_db".to_string(), json!("tenant_42"));
+                options.insert("include_metrics".to_string(), json!(true));
+                options
+            }),
+            Response::ScoredRecordSet(vec![crate::types::ScoredRecord {
+                score: 3.25,
+                record: {
+                    let mut record = Record::new();
+                    record.insert("title".to_string(), json!("Why Rust"));
+                    record
+                },
+            }]),
+            Response::JobStarted(crate::types::JobId(7)),
+            Response::JobStatus {
+                job_id: crate::types::JobId(7),
+                status: crate::types::JobStatus::Running { progress: Some(0.5) },
+            },
+            Response::JobStatus {
+                job_id: crate::types::JobId(8),
+                status: crate::types::JobStatus::Failed("disk full".to_string()),
+            },
+            Response::BulkWriteError(crate::types::BulkWriteError {
+                succeeded: 2,
+                failures: vec![crate::types::WriteFailure {
+                    index: 1,
+                    code: crate::types::ErrorCode::DuplicateKey,
+                    message: "record already exists".to_string(),
+                    conflicting_record_id: Some("user-42".to_string()),
+                }],
+            }),
+            Response::ValueSet(vec![json!("acme"), json!(null), json!(42)]),
+            Response::RecordSetPage {
+                records: vec![Record::new()],
+                cursor: Some(crate::types::Cursor("c-91f3".to_string())),
+            },
+            Response::RecordSetPage { records: Vec::new(), cursor: None },
+            Response::CollectionDefaults(crate::types::CollectionDefaults {
+                default_limit: Some(100),
+                ..Default::default()
+            }),
+            Response::QueryPlan(crate::types::QueryPlan {
+                index: Some("level".to_string()),
+                scan_type: crate::types::ScanType::IndexLookup,
+                estimated_rows: 10,
+                stages: vec![
+                    crate::types::PlanStage {
+                        name: "IndexLookup".to_string(),
+                        detail: Some(r#"level = "error""#.to_string()),
+                        estimated_rows: 1_200,
+                    },
+                    crate::types::PlanStage {
+                        name: "Limit".to_string(),
+                        detail: None,
+                        estimated_rows: 10,
+                    },
+                ],
+            }),
+            Response::QueryPlanJson(r#"{"scan":"index","index":"level","rows":10}"#.to_string()),
+            Response::AggregateResults(vec![crate::types::AggregateResult {
+                group: HashMap::from([("region".to_string(), json!("EU"))]),
+                values: HashMap::from([("orders".to_string(), json!(12)), ("revenue".to_string(), json!(340.5))]),
+            }]),
+            Response::ScanPartitions(vec![
+                crate::types::Cursor("scan-0".to_string()),
+                crate::types::Cursor("scan-1".to_string()),
+            ]),
+            Response::OperationList(vec![crate::types::OperationInfo {
+                op_id: crate::types::OperationId(314),
+                request_type: "FindRecords".to_string(),
+                duration_micros: 93_000_000,
+                target: Some("logs.events".to_string()),
+            }]),
+            Response::RecordsUpdated(1_204),
+            Response::ConnectionList(vec![crate::types::ConnectionInfo {
+                conn_id: crate::types::ConnectionId(12),
+                peer: "10.0.0.7:52114".to_string(),
+                user: Some("reporting".to_string()),
+                idle_micros: 0,
+                in_flight_ops: 3,
+            }]),
+            Response::RecordsDeleted(48_000),
+            Response::VersionedRecord(Some((Record::from([("name".to_string(), json!("Alice"))]), 4))),
+            Response::VersionedRecord(None),
+            Response::RecordVersion(5),
+            Response::VersionConflict { current_version: Some(6) },
+            Response::BatchWriteResponse(crate::types::BatchWriteResponse {
+                results: vec![
+                    crate::types::WriteResult::Applied { version: Some(1) },
+                    crate::types::WriteResult::Failed(crate::types::WriteFailure {
+                        index: 1,
+                        code: crate::types::ErrorCode::VersionConflict,
+                        message: "expected version 2, found 3".to_string(),
+                        conflicting_record_id: None,
+                    }),
+                    crate::types::WriteResult::Skipped,
+                    crate::types::WriteResult::Skipped,
+                ],
+            }),
+            Response::ViewStats(crate::types::ViewStats {
+                record_count: 14,
+                last_refreshed_micros: Some(1_700_000_000_000_000),
+                staleness_micros: Some(42_000_000),
+                refreshing: false,
+            }),
+            Response::RecordImage(Some(Record::from([("status".to_string(), json!("open"))]))),
+            Response::RecordImage(None),
+            Response::RecordCreated { id: "01HV8Z6Q3K9T".to_string() },
+            Response::TimeBuckets(vec![crate::types::TimeBucket {
+                start_micros: 1_700_000_000_000_000,
+                values: Record::from([("avg_load".to_string(), json!(0.42))]),
+            }]),
+            Response::DistinctEstimate {
+                estimate: 10_240,
+                lower_bound: 10_031,
+                upper_bound: 10_449,
+            },
+            Response::TransactionStarted(crate::types::TransactionId(7)),
+            Response::TransactionAborted { reason: "write conflict on accounts/alice".to_string(), code: None },
+            Response::TransactionAborted {
+                reason: "open for longer than 30s".to_string(),
+                code: Some(crate::types::ErrorCode::TransactionExpired),
+            },
+            Response::TransactionStatus(crate::types::TransactionStatus {
+                tx_id: crate::types::TransactionId(7),
+                state: crate::types::TransactionState::Active,
+                options: Default::default(),
+                age: crate::types::DurationMs(1_200),
+                expires_in: Some(crate::types::DurationMs(28_800)),
+            }),
+            Response::TransactionPrepared { token: crate::types::PreparedToken("p-7f3a".to_string()) },
+            Response::AtomicWriteAborted(crate::types::WriteFailure {
+                index: 1,
+                code: crate::types::ErrorCode::VersionConflict,
+                message: "expected version 9, found 10".to_string(),
+                conflicting_record_id: None,
+            }),
+            Response::HelloAck {
+                server_version: crate::types::ProtocolVersion { major: 2, minor: 0 },
+                negotiated_features: vec!["envelopes".to_string()],
+                deprecations: vec![crate::types::Deprecation {
+                    variant: "ExecuteBatchGet".to_string(),
+                    since: crate::types::ProtocolVersion { major: 2, minor: 0 },
+                    sunset: crate::types::ProtocolVersion { major: 3, minor: 0 },
+                    replacement: Some("FindRecords".to_string()),
+                }],
+            },
+            Response::WithReceipt {
+                data: Box::new(Response::RecordVersion(3)),
+                receipt: crate::types::WriteReceipt {
+                    sequence_number: 918_274,
+                    commit_timestamp_micros: 1_700_000_000_123_456,
+                },
+            },
+            Response::DryRunReport(crate::types::DryRunReport {
+                records_affected: 1_204,
+                constraints_checked: vec!["users.email_unique".to_string()],
+                warnings: vec!["view active_users reads from users".to_string()],
+            }),
+            Response::ConfirmationRequired {
+                token: crate::types::ConfirmationToken("c0nf1rm-7f3a".to_string()),
+                expires_in: crate::types::DurationMs::from_secs(30),
+            },
+            Response::RecordSetChunk {
+                records: vec![HashMap::from([("id".to_string(), json!("u1"))])],
+                seq: 0,
+            },
+            Response::RecordSetEnd { total: 1 },
+            Response::Cancelled,
+            Response::StreamItem {
+                stream_id: crate::types::StreamId(7),
+                seq: 0,
+                payload: Box::new(Response::RecordSetChunk {
+                    records: vec![HashMap::from([("id".to_string(), json!("u1"))])],
+                    seq: 0,
+                }),
+            },
+            Response::StreamEnd {
+                stream_id: crate::types::StreamId(7),
+                summary: Some(Box::new(Response::RecordSetEnd { total: 1 })),
+            },
+            Response::StreamEnd { stream_id: crate::types::StreamId(8), summary: None },
+            Response::CollectionVerified(crate::types::VerificationReport {
+                record_count: 2,
+                content_checksum: 0x9e37_79b9_7f4a_7c15,
+                index_checksums: HashMap::from([("orders_by_customer".to_string(), 0x1234)]),
+                corruptions: vec![crate::types::Corruption::DanglingIndexEntry {
+                    index: "orders_by_customer".to_string(),
+                    record_id: "o17".to_string(),
+                }],
+            }),
         ];
         
         for response in responses {
@@ -419,12 +2407,13 @@ mod tests {
 #[test]
 fn test_result_metrics_serialization() {
     // 1. Create the inner data (the actual result of a query).
-    let record_set = RecordSet { records: vec![] };
+    let record_set = RecordSet { records: vec![], ..Default::default() };
     let inner_response = Response::RecordSet(record_set);
 
     // 2. Create the metrics data.
     let metrics = QueryMetrics {
         execution_time_micros: 12345,
+        ..Default::default()
     };
 
     // 3. Wrap them in the new ResultMetrics response.
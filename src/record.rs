@@ -0,0 +1,68 @@
+// File: src/record.rs
+// =============================================================================
+// This file provides typed accessors on `Record`. Reading a field out of a
+// `serde_json::Value` map by hand takes a chain of `get` and `as_*` calls
+// that collapses every failure into `None`; these report which field was
+// missing or had the wrong type instead.
+
+use crate::error::ProtocolError;
+use crate::types::{FieldPath, Record};
+use serde_json::Value;
+
+/// Typed accessors for `Record`. Every `path` is interpreted as a
+/// `FieldPath`, so `"address.city"` and `"tags[0]"` reach nested values.
+pub trait RecordExt {
+    /// Returns the value at `path`, failing with `MissingField` if any step
+    /// of the path is absent.
+    fn get_nested(&self, path: &str) -> Result<&Value, ProtocolError>;
+
+    fn get_str(&self, path: &str) -> Result<&str, ProtocolError> {
+        typed(path, self.get_nested(path)?, "string", Value::as_str)
+    }
+
+    /// Fails with `FieldType` for floats and for integers outside `i64`.
+    fn get_i64(&self, path: &str) -> Result<i64, ProtocolError> {
+        typed(path, self.get_nested(path)?, "integer", Value::as_i64)
+    }
+
+    /// Accepts any number, converting integers to `f64`.
+    fn get_f64(&self, path: &str) -> Result<f64, ProtocolError> {
+        typed(path, self.get_nested(path)?, "number", Value::as_f64)
+    }
+
+    fn get_bool(&self, path: &str) -> Result<bool, ProtocolError> {
+        typed(path, self.get_nested(path)?, "bool", Value::as_bool)
+    }
+
+    fn get_array(&self, path: &str) -> Result<&Vec<Value>, ProtocolError> {
+        typed(path, self.get_nested(path)?, "array", Value::as_array)
+    }
+}
+
+impl RecordExt for Record {
+    fn get_nested(&self, path: &str) -> Result<&Value, ProtocolError> {
+        let path = FieldPath::parse(path)?;
+        path.resolve(self).ok_or(ProtocolError::MissingField(path.0))
+    }
+}
+
+fn typed<'a, T>(
+    path: &str,
+    value: &'a Value,
+    expected: &'static str,
+    convert: impl FnOnce(&'a Value) -> Option<T>,
+) -> Result<T, ProtocolError> {
+    convert(value).ok_or_else(|| ProtocolError::FieldType { path: path.to_string(), expected, found: type_name(value) })
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
@@ -3,51 +3,127 @@
 // This file defines the top-level `Request` enum. This is the single, unified
 // type that represents every possible command a client can send to the server.
 
-use crate::types::{BatchRequest, Filter, QueryOptions, Record};
+use crate::types::{Aggregation, AggregationPipeline, BatchRequest, BatchWriteRequest, CollectionDefaults, CollectionOptions, ConfirmationToken, ConnectionId, Cursor, Direction, DurationMs, ExplainFormat, FieldPath, Filter, IndexKind, JobId, ListOptions, OperationId, PreparedToken, ProtocolVersion, QueryOptions, RefreshPolicy, Record, ReturnDocument, ScanThrottle, TimeRange, TransactionId, TransactionOptions, UpdateOps, VerifyMode, WriteOperation};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 
 /// The primary enum representing all possible client requests.
+///
+/// New variants are added in minor releases, so matches outside this crate
+/// need a wildcard arm.
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Request {
     // --- Database Management ---
     CreateDatabase { db_name: String },
+    /// May be answered with `ConfirmationRequired`; see `Confirmed`.
     DropDatabase { db_name: String },
-    ListDatabases,
+    ListDatabases {
+        #[serde(default)]
+        options: ListOptions,
+    },
 
     // --- Collection Management ---
-    ListCollections,
-    CreateCollection { db_name: String, collection_name: String },
+    ListCollections {
+        #[serde(default)]
+        options: ListOptions,
+    },
+    CreateCollection {
+        db_name: String,
+        collection_name: String,
+        #[serde(default)]
+        options: CollectionOptions,
+    },
+    /// May be answered with `ConfirmationRequired`; see `Confirmed`.
     DropCollection { db_name: String, collection_name: String },
     GetStats,
     Flush,
 
     // --- Index Management ---
-    CreateIndex { db_name: String, collection: String, field_name: String },
-    DropIndex { db_name: String, collection: String, field_name: String },
-    ListIndexes { db_name: String, collection: String },
+    // `field_name` may be nested, such as `"address.city"`.
+    CreateIndex {
+        db_name: String,
+        collection: String,
+        field_name: FieldPath,
+        #[serde(default)]
+        kind: IndexKind,
+    },
+    DropIndex { db_name: String, collection: String, field_name: FieldPath },
+    ListIndexes {
+        db_name: String,
+        collection: String,
+        #[serde(default)]
+        options: ListOptions,
+    },
 
     // --- Record Operations (CRUD) ---
-    CreateRecord { db_name: String, collection: String, record_id: String, data: Record },
-    UpdateRecord { db_name: String, collection: String, record_id: String, data: Record },
-    UpsertRecord { db_name: String, collection: String, record_id: String, data: Record },
-    GetRecord { db_name: String, collection: String, record_id: String },
-    DeleteRecord { db_name: String, collection: String, record_id: String, cascade: bool },
+    // A `db_name` of `None` on record and query requests uses the database
+    // selected for the connection with `UseDatabase`.
+    // Writes carrying an `expected_version` only apply if the record is still
+    // at that version (compare-and-swap). They then answer with the new
+    // `RecordVersion`, or with `VersionConflict` if the record has moved on.
+    // A `ttl` makes the server delete the record that long after the write.
+    // A `return_document` answers with a `RecordImage` of the record before or
+    // after the write, in place of the usual response.
+    CreateRecord {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        data: Record,
+        ttl: Option<DurationMs>,
+    },
+    UpdateRecord {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        data: Record,
+        expected_version: Option<u64>,
+        return_document: Option<ReturnDocument>,
+    },
+    UpsertRecord {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        data: Record,
+        expected_version: Option<u64>,
+        ttl: Option<DurationMs>,
+        return_document: Option<ReturnDocument>,
+    },
+    /// With `with_version` set, answered with a `VersionedRecord` instead of a `Record`.
+    GetRecord {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        #[serde(default)]
+        with_version: bool,
+    },
+    DeleteRecord {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        cascade: bool,
+        expected_version: Option<u64>,
+        return_document: Option<ReturnDocument>,
+    },
+    /// Racy behind pooled connections; prefer `InsertRecord`, which returns
+    /// the generated id directly.
     GetLastInsertId,
 
     // --- Querying & Relational ---
     FindRecords {
-        db_name: String,
+        db_name: Option<String>,
         collection: String,
         filter: Filter,
         options: Option<QueryOptions>,
     },
     CountRecords {
-        db_name: String,
+        db_name: Option<String>,
         collection: String,
         filter: Filter,
     },
     GetRecordWithRelated {
-        db_name: String,
+        db_name: Option<String>,
         primary_collection: String,
         primary_record_id: String,
         relation_key_field: String,
@@ -55,10 +131,478 @@ pub enum Request {
     },
     ExecuteBatchGet(BatchRequest),
     Search {
-        db_name: String,
+        db_name: Option<String>,
         collection: String,
         query: String,
         field: Option<String>, // An optional field to search within. If None, search all fields.
 
     },
-}
\ No newline at end of file
+    /// Runs one query across several collections, e.g. per-day log partitions.
+    FindRecordsMulti {
+        db_name: Option<String>,
+        collections: Vec<String>,
+        filter: Filter,
+        options: Option<QueryOptions>,
+    },
+
+    // --- Collection Introspection ---
+    /// Infers field names, types and occurrence rates from up to `sample_size` records.
+    DescribeCollection { db_name: Option<String>, collection: String, sample_size: usize },
+
+    // --- Server ---
+    /// Asks for the server's clocks so the client can compute its clock skew.
+    GetServerTime,
+
+    // --- Connection ---
+    /// Sets a knob that applies to every later request on this connection,
+    /// e.g. `default_db`, `consistency`, `compression_threshold` or `include_metrics`.
+    SetConnectionOption { key: String, value: Value },
+    GetConnectionOptions,
+    /// Selects the default database for record and query requests that omit `db_name`.
+    /// Schema requests (collections, indexes, collection defaults, views) always
+    /// name their database, so a stale default can never redirect a schema change.
+    UseDatabase { db_name: String },
+
+    // --- Full-Text Search ---
+    /// Like `FindRecords`, but matches are ranked by the relevance of the
+    /// filter's `TextSearch` clauses and returned with their scores.
+    SearchRecords {
+        db_name: Option<String>,
+        collection: String,
+        filter: Filter,
+        options: Option<QueryOptions>,
+    },
+
+    // --- Jobs ---
+    // Requests that start long-running work may answer with `Response::JobStarted`;
+    // the job is then polled and cancelled through these requests.
+    GetJobStatus { job_id: JobId },
+    CancelJob { job_id: JobId },
+
+    // --- Vector Search ---
+    /// Finds the `k` records whose `field` vector is nearest to `query_vector`,
+    /// optionally restricted by `filter`. Answered with a `ScoredRecordSet`
+    /// scored by the distance metric of the field's vector index.
+    VectorSearch {
+        db_name: Option<String>,
+        collection: String,
+        field: FieldPath,
+        query_vector: Vec<f32>,
+        k: usize,
+        filter: Option<Filter>,
+    },
+
+    // --- Distinct Values ---
+    /// Returns each distinct value of `field` among the records matching
+    /// `filter` (or all records), e.g. to build facets. Answered with a `ValueSet`.
+    DistinctValues {
+        db_name: Option<String>,
+        collection: String,
+        field: FieldPath,
+        filter: Option<Filter>,
+    },
+
+    // --- Cursors ---
+    /// Fetches the next page of a `RecordSetPage`.
+    FetchMore { cursor: Cursor },
+    /// Releases a cursor the client will not read to the end.
+    CloseCursor { cursor: Cursor },
+
+    // --- Collection Defaults ---
+    /// Replaces the collection's query defaults; `None` fields clear a default.
+    /// Like the other schema requests, it names its database explicitly.
+    SetCollectionDefaults { db_name: String, collection: String, defaults: CollectionDefaults },
+    GetCollectionDefaults { db_name: String, collection: String },
+
+    // --- Query Planning ---
+    /// Returns the plan the server would use for the equivalent
+    /// `FindRecords`, without running it. Answered with `QueryPlan`, or with
+    /// `QueryPlanJson` when `format` is `ExplainFormat::Json`.
+    ExplainQuery {
+        db_name: Option<String>,
+        collection: String,
+        filter: Filter,
+        options: Option<QueryOptions>,
+        #[serde(default)]
+        format: ExplainFormat,
+    },
+
+    // --- Aggregation ---
+    /// Groups the records matching `filter` by the `group_by` fields and
+    /// computes `aggregations` for each group. An empty `group_by` puts every
+    /// record in one group. Answered with `AggregateResults`.
+    Aggregate {
+        db_name: Option<String>,
+        collection: String,
+        filter: Filter,
+        group_by: Vec<FieldPath>,
+        aggregations: Vec<Aggregation>,
+    },
+
+    // --- Parallel Scans ---
+    /// Splits the collection into up to `partitions` disjoint ranges and
+    /// answers with one `Cursor` per range. Each cursor is read independently
+    /// with `FetchMore`, so separate workers can export the collection in
+    /// parallel without overlap. `throttle` applies to each cursor separately.
+    PlanParallelScan {
+        db_name: Option<String>,
+        collection: String,
+        partitions: usize,
+        #[serde(default)]
+        throttle: Option<ScanThrottle>,
+    },
+
+    // --- Aggregation Pipelines ---
+    /// Runs `pipeline` over the collection. The rows left after the last
+    /// stage are returned as a `RecordSet`.
+    AggregatePipeline { db_name: Option<String>, collection: String, pipeline: AggregationPipeline },
+
+    // --- Impersonation ---
+    /// Executes `inner` with the identity and permissions of `principal`, for
+    /// gateways that hold one service credential on behalf of many users. Only
+    /// connections with admin credentials may send it. Answered with the
+    /// response to `inner`.
+    RunAs { principal: String, inner: Box<Request> },
+
+    // --- Partial Updates ---
+    /// Applies `ops` to a record in place, so simple field changes need no
+    /// read-modify-write round trip. Answered with the updated record.
+    PatchRecord {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        ops: UpdateOps,
+        expected_version: Option<u64>,
+    },
+
+    // --- Operations ---
+    /// Lists the requests the server is currently executing.
+    ListOperations,
+    /// Aborts a running request; its client receives an error response.
+    KillOperation { op_id: OperationId },
+
+    // --- Bulk Updates ---
+    /// Applies `ops` to every record matching `filter`. Answered with
+    /// `RecordsUpdated`.
+    UpdateRecords { db_name: Option<String>, collection: String, filter: Filter, ops: UpdateOps },
+
+    // --- Connection Management ---
+    /// Lists every client connection to the server.
+    ListConnections,
+    /// Closes a client's connection, aborting its in-flight requests.
+    KillConnection { conn_id: ConnectionId },
+
+    // --- Bulk Deletes ---
+    /// Deletes every record matching `filter` in one atomic step, with the
+    /// same `cascade` semantics as `DeleteRecord`. Answered with `RecordsDeleted`.
+    DeleteRecords { db_name: Option<String>, collection: String, filter: Filter, cascade: bool },
+
+    // --- Atomic Find-and-Modify ---
+    // Both requests pick the first record matching `filter` in `sort_by` order
+    // (any match when `None`), modify it and answer with a `Record`, all as one
+    // atomic step; `Record(None)` means nothing matched.
+    /// Applies `ops` to the record and returns the chosen image of it.
+    FindOneAndUpdate {
+        db_name: Option<String>,
+        collection: String,
+        filter: Filter,
+        sort_by: Option<(FieldPath, Direction)>,
+        ops: UpdateOps,
+        return_document: ReturnDocument,
+    },
+    /// Deletes the record and returns it as it was.
+    FindOneAndDelete {
+        db_name: Option<String>,
+        collection: String,
+        filter: Filter,
+        sort_by: Option<(FieldPath, Direction)>,
+    },
+
+    // --- Batch Writes ---
+    /// Runs a mix of creates, updates, upserts and deletes in one request.
+    /// Answered with a `BatchWriteResponse` holding one result per operation.
+    ExecuteBatchWrite(BatchWriteRequest),
+
+    // --- Record Expiry ---
+    /// Sets a record to expire `ttl` from now, or clears its expiry
+    /// when `None`.
+    SetRecordTtl {
+        db_name: Option<String>,
+        collection: String,
+        record_id: String,
+        ttl: Option<DurationMs>,
+    },
+
+    // --- Materialized Views ---
+    // A view stores the output of `pipeline` run over the `source` collection
+    // and is read with the normal record and query requests under its `name`.
+    // It is dropped with `DropCollection`. Like `CreateCollection`, the view
+    // requests name their database explicitly.
+    CreateMaterializedView {
+        db_name: String,
+        name: String,
+        source: String,
+        pipeline: AggregationPipeline,
+        refresh: RefreshPolicy,
+    },
+    /// Recomputes a view in the background. Answered with `JobStarted`.
+    RefreshView { db_name: String, name: String },
+    GetViewStats { db_name: String, name: String },
+
+    // --- Generated Ids ---
+    /// Like `CreateRecord`, but the server assigns the record id and answers
+    /// with `RecordCreated`.
+    InsertRecord {
+        db_name: Option<String>,
+        collection: String,
+        data: Record,
+        ttl: Option<DurationMs>,
+    },
+
+    // --- Time Series ---
+    /// Splits `range` of a time-series collection into buckets of
+    /// `bucket_micros` and computes `aggregations` over the points in each.
+    /// Answered with `TimeBuckets`; empty buckets are omitted.
+    DownsampleQuery {
+        db_name: Option<String>,
+        collection: String,
+        bucket_micros: u64,
+        aggregations: Vec<Aggregation>,
+        range: TimeRange,
+    },
+
+    // --- Approximate Queries ---
+    /// Estimates the number of distinct values of `field` among the records
+    /// matching `filter`, using a HyperLogLog sketch instead of materializing
+    /// the values as `DistinctValues` does. Answered with `DistinctEstimate`.
+    ApproxDistinct {
+        db_name: Option<String>,
+        collection: String,
+        field: FieldPath,
+        filter: Option<Filter>,
+    },
+
+    // --- Transactions ---
+    /// Opens a transaction on this connection. Answered with
+    /// `TransactionStarted`.
+    BeginTransaction {
+        #[serde(default)]
+        options: TransactionOptions,
+    },
+    /// Atomically applies every write made in the transaction. Answered with
+    /// `Success`, or `TransactionAborted` if it conflicted with another
+    /// transaction; either way `tx_id` is closed.
+    CommitTransaction { tx_id: TransactionId },
+    /// Discards every write made in the transaction and closes it.
+    RollbackTransaction { tx_id: TransactionId },
+    /// Executes `inner` inside the transaction `tx_id`: its reads see the
+    /// transaction's snapshot and its writes become visible only on commit.
+    /// Answered with the response to `inner`.
+    InTransaction { tx_id: TransactionId, inner: Box<Request> },
+
+    // --- Atomic Write Groups ---
+    /// Applies `operations` within one database as a single atomic unit:
+    /// either all of them take effect or none do. Unlike a transaction it
+    /// needs only one round trip and holds no server state between requests.
+    /// Answered with a `BatchWriteResponse` in which every result is
+    /// `Applied`, or with `AtomicWriteAborted`.
+    AtomicWriteGroup { db_name: Option<String>, operations: Vec<WriteOperation> },
+
+    // --- Handshake ---
+    /// Sent as the first message on a connection to agree on a protocol
+    /// version and optional features. Answered with `HelloAck`, or with
+    /// `Error` if the server cannot speak a compatible version. A server that
+    /// fails to decode it predates the handshake.
+    Hello {
+        client_version: ProtocolVersion,
+        /// Optional features the client can use, as `Capability::name` strings.
+        supported_features: Vec<String>,
+        /// Shown in `ListConnections` and server logs.
+        app_name: Option<String>,
+    },
+
+    // --- Dry Runs ---
+    /// Validates and plans `inner`, a write or DDL request, without applying
+    /// it. Answered with a `DryRunReport`, or with the error `inner` would have
+    /// failed with.
+    DryRun { inner: Box<Request> },
+
+    // --- Confirmation ---
+    /// Servers with drop protection enabled answer `DropDatabase`,
+    /// `DropCollection` and `DeleteRecords` with an empty filter with
+    /// `ConfirmationRequired` instead of executing them. The client then
+    /// resends the request as `inner`, unchanged, along with the issued
+    /// token before it expires. Answered with the response to `inner`, or
+    /// with `Error` if the token is unknown, expired or was issued for a
+    /// different request.
+    Confirmed { token: ConfirmationToken, inner: Box<Request> },
+
+    // --- Cancellation ---
+    /// Aborts the in-flight request sent in the `RequestEnvelope` with this
+    /// `request_id` on the same connection. The cancelled request is answered
+    /// with `Cancelled`, unless it had already completed. `Cancel` itself is
+    /// answered with `Success` either way.
+    Cancel { request_id: u64 },
+
+    // --- Integrity Verification ---
+    /// Computes the collection's checksums and, in `VerifyMode::Full`, checks
+    /// it for corruption. Answered with `CollectionVerified`.
+    VerifyCollection {
+        db_name: Option<String>,
+        collection: String,
+        #[serde(default)]
+        mode: VerifyMode,
+    },
+
+    // --- Savepoints ---
+    /// Marks the current state of the transaction `tx_id` under `name`,
+    /// replacing any savepoint of that name. Answered with `Success`.
+    CreateSavepoint { tx_id: TransactionId, name: String },
+    /// Discards the writes made in the transaction since the savepoint
+    /// `name` was created, along with any later savepoints, and keeps the
+    /// transaction open. Answered with `Success`, or `Error` if there is no
+    /// such savepoint.
+    RollbackToSavepoint { tx_id: TransactionId, name: String },
+
+    // --- Transaction Status ---
+    /// Reports the state of the transaction `tx_id` and how long it has
+    /// left. Answered with `TransactionStatus`, or `Error` if the server has
+    /// never seen or has forgotten the transaction.
+    GetTransactionStatus { tx_id: TransactionId },
+
+    // --- Two-Phase Commit ---
+    /// Makes every write of the transaction `tx_id` durable without applying
+    /// it, after which the transaction can no longer fail to commit, and
+    /// closes `tx_id`. Answered with `TransactionPrepared`, or
+    /// `TransactionAborted` if the writes conflict. Lets a client commit
+    /// together with another system: it prepares here, records the token
+    /// alongside the other system's work, then commits or aborts.
+    PrepareTransaction { tx_id: TransactionId },
+    /// Applies a prepared transaction. Works from any connection, including
+    /// after a restart. Answered with `Success`, also if it was already
+    /// committed.
+    CommitPrepared { token: PreparedToken },
+    /// Discards a prepared transaction. Answered with `Success`, also if it
+    /// was already aborted.
+    AbortPrepared { token: PreparedToken },
+
+    // --- Forward Compatibility ---
+    /// Stands in for a request this build could not decode because it uses
+    /// a variant added in a newer version, when the `Codec` was created with
+    /// `with_unknown_variants(true)`. Never sent on the wire; encoding it
+    /// fails.
+    ///
+    /// Must stay the last variant: serde numbers the variants that follow a
+    /// skipped one differently when decoding than when encoding.
+    #[serde(skip)]
+    Unknown,
+}
+
+impl Request {
+    /// Returns the name of this variant, e.g. for logging and metrics.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Request::CreateDatabase { .. } => "CreateDatabase",
+            Request::DropDatabase { .. } => "DropDatabase",
+            Request::ListDatabases { .. } => "ListDatabases",
+            Request::ListCollections { .. } => "ListCollections",
+            Request::CreateCollection { .. } => "CreateCollection",
+            Request::DropCollection { .. } => "DropCollection",
+            Request::GetStats => "GetStats",
+            Request::Flush => "Flush",
+            Request::CreateIndex { .. } => "CreateIndex",
+            Request::DropIndex { .. } => "DropIndex",
+            Request::ListIndexes { .. } => "ListIndexes",
+            Request::CreateRecord { .. } => "CreateRecord",
+            Request::UpdateRecord { .. } => "UpdateRecord",
+            Request::UpsertRecord { .. } => "UpsertRecord",
+            Request::GetRecord { .. } => "GetRecord",
+            Request::DeleteRecord { .. } => "DeleteRecord",
+            Request::GetLastInsertId => "GetLastInsertId",
+            Request::FindRecords { .. } => "FindRecords",
+            Request::CountRecords { .. } => "CountRecords",
+            Request::GetRecordWithRelated { .. } => "GetRecordWithRelated",
+            Request::ExecuteBatchGet(..) => "ExecuteBatchGet",
+            Request::Search { .. } => "Search",
+            Request::FindRecordsMulti { .. } => "FindRecordsMulti",
+            Request::DescribeCollection { .. } => "DescribeCollection",
+            Request::GetServerTime => "GetServerTime",
+            Request::SetConnectionOption { .. } => "SetConnectionOption",
+            Request::GetConnectionOptions => "GetConnectionOptions",
+            Request::UseDatabase { .. } => "UseDatabase",
+            Request::SearchRecords { .. } => "SearchRecords",
+            Request::GetJobStatus { .. } => "GetJobStatus",
+            Request::CancelJob { .. } => "CancelJob",
+            Request::VectorSearch { .. } => "VectorSearch",
+            Request::DistinctValues { .. } => "DistinctValues",
+            Request::FetchMore { .. } => "FetchMore",
+            Request::CloseCursor { .. } => "CloseCursor",
+            Request::SetCollectionDefaults { .. } => "SetCollectionDefaults",
+            Request::GetCollectionDefaults { .. } => "GetCollectionDefaults",
+            Request::ExplainQuery { .. } => "ExplainQuery",
+            Request::Aggregate { .. } => "Aggregate",
+            Request::PlanParallelScan { .. } => "PlanParallelScan",
+            Request::AggregatePipeline { .. } => "AggregatePipeline",
+            Request::RunAs { .. } => "RunAs",
+            Request::PatchRecord { .. } => "PatchRecord",
+            Request::ListOperations => "ListOperations",
+            Request::KillOperation { .. } => "KillOperation",
+            Request::UpdateRecords { .. } => "UpdateRecords",
+            Request::ListConnections => "ListConnections",
+            Request::KillConnection { .. } => "KillConnection",
+            Request::DeleteRecords { .. } => "DeleteRecords",
+            Request::FindOneAndUpdate { .. } => "FindOneAndUpdate",
+            Request::FindOneAndDelete { .. } => "FindOneAndDelete",
+            Request::ExecuteBatchWrite(..) => "ExecuteBatchWrite",
+            Request::SetRecordTtl { .. } => "SetRecordTtl",
+            Request::CreateMaterializedView { .. } => "CreateMaterializedView",
+            Request::RefreshView { .. } => "RefreshView",
+            Request::GetViewStats { .. } => "GetViewStats",
+            Request::InsertRecord { .. } => "InsertRecord",
+            Request::DownsampleQuery { .. } => "DownsampleQuery",
+            Request::ApproxDistinct { .. } => "ApproxDistinct",
+            Request::BeginTransaction { .. } => "BeginTransaction",
+            Request::CommitTransaction { .. } => "CommitTransaction",
+            Request::RollbackTransaction { .. } => "RollbackTransaction",
+            Request::InTransaction { .. } => "InTransaction",
+            Request::AtomicWriteGroup { .. } => "AtomicWriteGroup",
+            Request::Hello { .. } => "Hello",
+            Request::DryRun { .. } => "DryRun",
+            Request::Confirmed { .. } => "Confirmed",
+            Request::Cancel { .. } => "Cancel",
+            Request::VerifyCollection { .. } => "VerifyCollection",
+            Request::CreateSavepoint { .. } => "CreateSavepoint",
+            Request::RollbackToSavepoint { .. } => "RollbackToSavepoint",
+            Request::GetTransactionStatus { .. } => "GetTransactionStatus",
+            Request::PrepareTransaction { .. } => "PrepareTransaction",
+            Request::CommitPrepared { .. } => "CommitPrepared",
+            Request::AbortPrepared { .. } => "AbortPrepared",
+            Request::Unknown => "Unknown",
+        }
+    }
+}
+
+/// A request tagged with a caller-chosen id. The server echoes the id in the
+/// `ResponseEnvelope` it answers with, so a client can keep several requests
+/// in flight on one connection and match responses that arrive out of order.
+///
+/// The envelope also carries context that the server echoes into its
+/// slow-query log and metrics, so operators can attribute expensive queries
+/// to application features. It applies to the whole request, including any
+/// request wrapped by `RunAs`, `InTransaction`, `DryRun` or `Confirmed`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RequestEnvelope {
+    pub request_id: u64,
+    pub body: Request,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+impl RequestEnvelope {
+    /// Wraps `body` with no comment or labels.
+    pub fn new(request_id: u64, body: Request) -> Self {
+        RequestEnvelope { request_id, body, comment: None, labels: HashMap::new() }
+    }
+}
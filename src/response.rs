@@ -3,32 +3,42 @@
 // This file defines the top-level `Response` enum. This is the single, unified
 // type that represents every possible reply the server can send to a client.
 
-use crate::types::{BatchResponse, DbStats, Record, RecordSet};
+use crate::types::{AggregateResult, BatchResponse, BatchWriteResponse, BulkWriteError, CollectionDefaults, CollectionSchema, ConfirmationToken, ConnectionInfo, Cursor, DbStats, Deprecation, DryRunReport, DurationMs, ErrorCode, IndexInfo, JobId, JobStatus, OperationInfo, Page, PreparedToken, ProtocolVersion, QueryPlan, Record, RecordSet, ScoredRecord, StreamId, TaggedRecord, TimeBucket, TransactionId, TransactionStatus, VerificationReport, ViewStats, WriteFailure, WriteReceipt};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 
 /// A struct to hold performance metrics for a query.
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 pub struct QueryMetrics {
     pub execution_time_micros: u64,
+    /// Whether the sort kept only the best `top_k` (or `offset + limit`)
+    /// records in a bounded heap instead of sorting every match.
+    #[serde(default)]
+    pub used_top_k: bool,
     // More planned for later, like records_scanned, etc.
 }
 
 /// The primary enum representing all possible server responses.
+///
+/// New variants are added in minor releases, so matches outside this crate
+/// need a wildcard arm.
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Response {
     // --- General Responses ---
     Success,
     Error(String),
 
     // --- Database Management Responses ---
-    DatabaseList(Vec<String>),
+    DatabaseList(Page<String>),
     DatabaseCreated(bool),
     DatabaseDropped(bool),
 
     // --- Collection Management Responses ---
-    CollectionList(Vec<String>),
+    CollectionList(Page<String>),
     Stats(DbStats),
-    IndexList(Vec<String>),
+    IndexList(Page<IndexInfo>),
 
     // --- Record & Query Responses ---
     Record(Option<Record>),
@@ -45,4 +55,275 @@ pub enum Response {
         data: Box<Response>, // The original response (e.g., RecordSet)
         metrics: QueryMetrics,
     },
-}
\ No newline at end of file
+
+    /// Records from several collections, each tagged with its source.
+    TaggedRecordSet(Vec<TaggedRecord>),
+
+    // --- Collection Introspection Responses ---
+    CollectionSchema(CollectionSchema),
+
+    // --- Server Responses ---
+    ServerTime {
+        /// Wall-clock time in microseconds since the Unix epoch.
+        wall_clock_micros: u64,
+        /// A monotonic clock reading in microseconds. Only differences between
+        /// readings from the same server are meaningful.
+        monotonic_micros: u64,
+    },
+
+    // --- Connection Responses ---
+    ConnectionOptions(HashMap<String, Value>),
+
+    // --- Full-Text Search Responses ---
+    ScoredRecordSet(Vec<ScoredRecord>),
+
+    // --- Job Responses ---
+    /// The request started a long-running job that can be polled with `GetJobStatus`.
+    JobStarted(JobId),
+    JobStatus { job_id: JobId, status: JobStatus },
+
+    // --- Write Error Responses ---
+    /// Some items of a bulk or batch write failed; the rest were applied.
+    BulkWriteError(BulkWriteError),
+
+    // --- Distinct Value Responses ---
+    ValueSet(Vec<Value>),
+
+    // --- Cursor Responses ---
+    /// One page of a query's results. A `cursor` of `None` means this was the
+    /// last page and the server has already released the cursor.
+    RecordSetPage { records: Vec<Record>, cursor: Option<Cursor> },
+
+    // --- Collection Defaults Responses ---
+    CollectionDefaults(CollectionDefaults),
+
+    // --- Query Planning Responses ---
+    QueryPlan(QueryPlan),
+
+    // --- Aggregation Responses ---
+    AggregateResults(Vec<AggregateResult>),
+
+    // --- Parallel Scan Responses ---
+    /// One cursor per scan partition. There may be fewer than requested,
+    /// e.g. for small collections.
+    ScanPartitions(Vec<Cursor>),
+
+    // --- Operation Responses ---
+    OperationList(Vec<OperationInfo>),
+
+    // --- Bulk Update Responses ---
+    /// The number of records an `UpdateRecords` changed.
+    RecordsUpdated(u64),
+
+    // --- Connection Management Responses ---
+    ConnectionList(Vec<ConnectionInfo>),
+
+    // --- Bulk Delete Responses ---
+    /// The number of records a `DeleteRecords` removed, not counting cascades.
+    RecordsDeleted(u64),
+
+    // --- Record Version Responses ---
+    /// A record with the version it is currently at, for `GetRecord` with
+    /// `with_version` set. `None` if the record does not exist.
+    VersionedRecord(Option<(Record, u64)>),
+    /// The version a record is at after a write that carried `expected_version`.
+    RecordVersion(u64),
+    /// A write's `expected_version` did not match. `current_version` is
+    /// `None` if the record no longer exists.
+    VersionConflict { current_version: Option<u64> },
+
+    // --- Batch Write Responses ---
+    BatchWriteResponse(BatchWriteResponse),
+
+    // --- Materialized View Responses ---
+    ViewStats(ViewStats),
+
+    // --- Record Image Responses ---
+    /// The image a write's `return_document` asked for. `None` when there is
+    /// no such image, e.g. `Before` for an upsert that created the record.
+    RecordImage(Option<Record>),
+
+    // --- Generated Id Responses ---
+    /// The id the server assigned to a record created with `InsertRecord`.
+    RecordCreated { id: String },
+
+    // --- Time Series Responses ---
+    /// Downsampled buckets in time order.
+    TimeBuckets(Vec<TimeBucket>),
+
+    // --- Approximate Query Responses ---
+    /// A distinct-count estimate; the true count lies within
+    /// `[lower_bound, upper_bound]` with the sketch's confidence (about 95%).
+    DistinctEstimate {
+        estimate: u64,
+        lower_bound: u64,
+        upper_bound: u64,
+    },
+
+    // --- Transaction Responses ---
+    TransactionStarted(TransactionId),
+    /// The transaction could not commit and none of its writes were applied.
+    /// Also answers any request on a transaction that has expired, with a
+    /// `code` of `TransactionExpired`.
+    TransactionAborted {
+        reason: String,
+        #[serde(default)]
+        code: Option<ErrorCode>,
+    },
+
+    // --- Atomic Write Group Responses ---
+    /// An `AtomicWriteGroup` was rolled back because the operation described
+    /// by the failure could not be applied. No operation took effect.
+    AtomicWriteAborted(WriteFailure),
+
+    // --- Handshake Responses ---
+    HelloAck {
+        server_version: ProtocolVersion,
+        /// The features from the client's `supported_features` that the
+        /// server also supports and has enabled for this connection.
+        negotiated_features: Vec<String>,
+        /// Request variants this server will stop accepting.
+        #[serde(default)]
+        deprecations: Vec<Deprecation>,
+    },
+
+    // --- Write Receipt Responses ---
+    /// Wraps the response to a write with its journal position. Only sent on
+    /// connections that negotiated the `"write_receipts"` feature in `Hello`;
+    /// clients unwrap it like `ResultMetrics`.
+    WithReceipt { data: Box<Response>, receipt: WriteReceipt },
+
+    // --- Dry Run Responses ---
+    DryRunReport(DryRunReport),
+
+    // --- Confirmation Responses ---
+    /// The destructive request was not executed. Resend it wrapped in
+    /// `Confirmed` with `token` within `expires_in` to proceed.
+    ConfirmationRequired { token: ConfirmationToken, expires_in: DurationMs },
+
+    // --- Streaming Responses ---
+    /// One batch of the results of a query sent with `stream` set. `seq`
+    /// starts at 0 and increases by one per chunk, so a client can detect
+    /// a gap.
+    RecordSetChunk { records: Vec<Record>, seq: u64 },
+    /// Ends a streamed result; `total` is the number of records sent across
+    /// all chunks.
+    RecordSetEnd { total: u64 },
+
+    // --- Cancellation Responses ---
+    /// The request was aborted by a `Cancel` before it completed. Writes it
+    /// had not finished are rolled back; a cancelled stream ends with this
+    /// instead of `RecordSetEnd`.
+    Cancelled,
+
+    // --- Stream Responses ---
+    /// One part of a multi-part result such as a cursor, export, change
+    /// stream or file download. Items of a stream arrive in `seq` order,
+    /// starting at 0 with no gaps, and may be interleaved with other
+    /// responses on the connection.
+    StreamItem { stream_id: StreamId, seq: u64, payload: Box<Response> },
+    /// The last message of a stream; no `StreamItem` with this `stream_id`
+    /// follows. `summary` carries the stream's final result, if it has one,
+    /// e.g. the totals of an export. A stream that failed ends with an
+    /// `Error` summary.
+    StreamEnd { stream_id: StreamId, summary: Option<Box<Response>> },
+
+    // --- Integrity Verification Responses ---
+    CollectionVerified(VerificationReport),
+
+    // --- Transaction Status Responses ---
+    TransactionStatus(TransactionStatus),
+
+    // --- Two-Phase Commit Responses ---
+    /// The transaction is prepared. Pass `token` to `CommitPrepared` or
+    /// `AbortPrepared`.
+    TransactionPrepared { token: PreparedToken },
+
+    // --- Raw Query Plan Responses ---
+    /// A plan requested with `ExplainFormat::Json`, as a JSON document.
+    QueryPlanJson(String),
+
+    // --- Forward Compatibility ---
+    /// Stands in for a response this build could not decode because it uses
+    /// a variant added in a newer version; see `Request::Unknown`.
+    ///
+    /// Must stay the last variant, like `Request::Unknown`.
+    #[serde(skip)]
+    Unknown,
+}
+
+impl Response {
+    /// Returns the name of this variant, e.g. for logging and metrics.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Response::Success => "Success",
+            Response::Error(..) => "Error",
+            Response::DatabaseList(..) => "DatabaseList",
+            Response::DatabaseCreated(..) => "DatabaseCreated",
+            Response::DatabaseDropped(..) => "DatabaseDropped",
+            Response::CollectionList(..) => "CollectionList",
+            Response::Stats(..) => "Stats",
+            Response::IndexList(..) => "IndexList",
+            Response::Record(..) => "Record",
+            Response::RecordSet(..) => "RecordSet",
+            Response::RecordCount(..) => "RecordCount",
+            Response::RecordDeleted(..) => "RecordDeleted",
+            Response::LastInsertId(..) => "LastInsertId",
+            Response::RecordWithRelated(..) => "RecordWithRelated",
+            Response::BatchResponse(..) => "BatchResponse",
+            Response::RecordIdSet(..) => "RecordIdSet",
+            Response::ResultMetrics { .. } => "ResultMetrics",
+            Response::TaggedRecordSet(..) => "TaggedRecordSet",
+            Response::CollectionSchema(..) => "CollectionSchema",
+            Response::ServerTime { .. } => "ServerTime",
+            Response::ConnectionOptions(..) => "ConnectionOptions",
+            Response::ScoredRecordSet(..) => "ScoredRecordSet",
+            Response::JobStarted(..) => "JobStarted",
+            Response::JobStatus { .. } => "JobStatus",
+            Response::BulkWriteError(..) => "BulkWriteError",
+            Response::ValueSet(..) => "ValueSet",
+            Response::RecordSetPage { .. } => "RecordSetPage",
+            Response::CollectionDefaults(..) => "CollectionDefaults",
+            Response::QueryPlan(..) => "QueryPlan",
+            Response::AggregateResults(..) => "AggregateResults",
+            Response::ScanPartitions(..) => "ScanPartitions",
+            Response::OperationList(..) => "OperationList",
+            Response::RecordsUpdated(..) => "RecordsUpdated",
+            Response::ConnectionList(..) => "ConnectionList",
+            Response::RecordsDeleted(..) => "RecordsDeleted",
+            Response::VersionedRecord(..) => "VersionedRecord",
+            Response::RecordVersion(..) => "RecordVersion",
+            Response::VersionConflict { .. } => "VersionConflict",
+            Response::BatchWriteResponse(..) => "BatchWriteResponse",
+            Response::ViewStats(..) => "ViewStats",
+            Response::RecordImage(..) => "RecordImage",
+            Response::RecordCreated { .. } => "RecordCreated",
+            Response::TimeBuckets(..) => "TimeBuckets",
+            Response::DistinctEstimate { .. } => "DistinctEstimate",
+            Response::TransactionStarted(..) => "TransactionStarted",
+            Response::TransactionAborted { .. } => "TransactionAborted",
+            Response::AtomicWriteAborted(..) => "AtomicWriteAborted",
+            Response::HelloAck { .. } => "HelloAck",
+            Response::WithReceipt { .. } => "WithReceipt",
+            Response::DryRunReport(..) => "DryRunReport",
+            Response::ConfirmationRequired { .. } => "ConfirmationRequired",
+            Response::RecordSetChunk { .. } => "RecordSetChunk",
+            Response::RecordSetEnd { .. } => "RecordSetEnd",
+            Response::Cancelled => "Cancelled",
+            Response::StreamItem { .. } => "StreamItem",
+            Response::StreamEnd { .. } => "StreamEnd",
+            Response::CollectionVerified(..) => "CollectionVerified",
+            Response::TransactionStatus(..) => "TransactionStatus",
+            Response::TransactionPrepared { .. } => "TransactionPrepared",
+            Response::QueryPlanJson(..) => "QueryPlanJson",
+            Response::Unknown => "Unknown",
+        }
+    }
+}
+
+/// A response tagged with the `request_id` of the `RequestEnvelope` it answers.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ResponseEnvelope {
+    pub request_id: u64,
+    pub body: Response,
+}
@@ -0,0 +1,125 @@
+// File: src/tokio_codec.rs
+// =============================================================================
+// This file adapts the frame format from `framing` to tokio-util's
+// `Encoder`/`Decoder` traits, so async clients and servers can wrap a socket
+// in `Framed` instead of hand-rolling the buffering. Only built with the
+// `tokio` feature.
+
+use crate::config::ProtocolConfig;
+use crate::error::ProtocolError;
+use crate::format::WireFormat;
+use crate::framing::{complete_message_len, decode_frames, encode_frames, Compression, FrameLimits, FrameOptions};
+use crate::{Request, Response};
+use bytes::BytesMut;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Frames one side of a connection: decodes incoming `In` messages and
+/// encodes outgoing `Out` ones. Use `AetherCodec` or `AetherServerCodec`.
+pub struct FramedCodec<In, Out> {
+    limits: FrameLimits,
+    options: FrameOptions,
+    messages: PhantomData<fn(Out) -> In>,
+}
+
+/// The client side of a connection: encodes `Request`s and decodes `Response`s.
+pub type AetherCodec = FramedCodec<Response, Request>;
+
+/// The server side of a connection: encodes `Response`s and decodes `Request`s.
+pub type AetherServerCodec = FramedCodec<Request, Response>;
+
+impl<In, Out> FramedCodec<In, Out> {
+    /// Creates a codec with the limits of `ProtocolConfig::default()`.
+    pub fn new() -> Self {
+        FramedCodec::from_config(&ProtocolConfig::default())
+    }
+
+    pub fn from_config(config: &ProtocolConfig) -> Self {
+        FramedCodec {
+            limits: FrameLimits::from_config(config),
+            options: FrameOptions::from_config(config),
+            messages: PhantomData,
+        }
+    }
+
+    /// Rejects incoming frames whose payload is larger than `max_frame_len`
+    /// bytes instead of buffering them, and splits outgoing messages into
+    /// frames no larger.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.limits.max_frame_len = max_frame_len;
+        self.options.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Serializes outgoing messages in `format`. Incoming frames are
+    /// deserialized according to their own flags.
+    pub fn with_format(mut self, format: WireFormat) -> Self {
+        self.options.format = format;
+        self
+    }
+
+    /// Compresses outgoing frames with `compression`. Incoming frames are
+    /// decompressed according to their own flags.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.options.compression = compression;
+        self
+    }
+
+    /// Appends a checksum to outgoing frames. Incoming frames are verified
+    /// whenever they carry one.
+    pub fn with_checksum(mut self, checksum: bool) -> Self {
+        self.options.checksum = checksum;
+        self
+    }
+}
+
+impl<In, Out> Default for FramedCodec<In, Out> {
+    fn default() -> Self {
+        FramedCodec::new()
+    }
+}
+
+// Implemented by hand so that neither message type has to be `Clone` or
+// `Debug` itself.
+impl<In, Out> Clone for FramedCodec<In, Out> {
+    fn clone(&self) -> Self {
+        FramedCodec { limits: self.limits, options: self.options, messages: PhantomData }
+    }
+}
+
+impl<In, Out> fmt::Debug for FramedCodec<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FramedCodec").field("limits", &self.limits).field("options", &self.options).finish()
+    }
+}
+
+impl<In, Out: Serialize> Encoder<Out> for FramedCodec<In, Out> {
+    type Error = ProtocolError;
+
+    fn encode(&mut self, item: Out, dst: &mut BytesMut) -> Result<(), ProtocolError> {
+        dst.extend_from_slice(&encode_frames(&item, self.options)?);
+        Ok(())
+    }
+}
+
+impl<In: DeserializeOwned, Out> Decoder for FramedCodec<In, Out> {
+    type Item = In;
+    type Error = ProtocolError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<In>, ProtocolError> {
+        decode_message(src, &self.limits)
+    }
+}
+
+/// Returns `None` until `src` holds every frame of the next message, then
+/// consumes and decodes them.
+fn decode_message<T: DeserializeOwned>(src: &mut BytesMut, limits: &FrameLimits) -> Result<Option<T>, ProtocolError> {
+    let Some(len) = complete_message_len(src, limits)? else {
+        return Ok(None);
+    };
+    let frames = src.split_to(len);
+    Ok(Some(decode_frames(&frames, limits)?))
+}
@@ -0,0 +1,206 @@
+// File: src/transport.rs
+// =============================================================================
+// This file provides blocking transports that send and receive framed
+// messages, so clients and servers can use the framing in this crate over a
+// socket without writing their own IO glue. `StreamTransport` works over any
+// `Read + Write` stream; TCP, Unix sockets and an in-process channel pair are
+// provided behind the `tcp`, `unix` and `memory` features.
+
+use crate::config::ProtocolConfig;
+use crate::error::ProtocolError;
+use crate::framing::{encode_frames, read_frame_with, read_message_frames, FrameOptions};
+use crate::{Request, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Read, Write};
+
+/// Sends and receives whole protocol messages, one call per message.
+///
+/// Implementations only move the encoded frames of a message, so the trait
+/// can be used as `Box<dyn Transport>` to pick TCP, a Unix socket or an
+/// in-process pair at runtime. `send_request`, `recv_response` and the other
+/// typed methods work on trait objects; `write_frame` and `read_frame` also
+/// handle envelopes, but only on concrete transports.
+pub trait Transport {
+    /// Sends the complete frames of one message.
+    fn send_frames(&mut self, frames: &[u8]) -> Result<(), ProtocolError>;
+
+    /// Blocks until the frames of one complete message have arrived and
+    /// returns them undecoded.
+    fn recv_frames(&mut self) -> Result<Vec<u8>, ProtocolError>;
+
+    /// How outgoing messages are serialized, compressed and checksummed.
+    fn frame_options(&self) -> FrameOptions;
+
+    /// The limits incoming messages are decoded with.
+    fn config(&self) -> &ProtocolConfig;
+
+    fn send_request(&mut self, request: &Request) -> Result<(), ProtocolError> {
+        self.send_frames(&encode_frames(request, self.frame_options())?)
+    }
+
+    fn send_response(&mut self, response: &Response) -> Result<(), ProtocolError> {
+        self.send_frames(&encode_frames(response, self.frame_options())?)
+    }
+
+    fn recv_request(&mut self) -> Result<Request, ProtocolError> {
+        read_frame_with(&mut self.recv_frames()?.as_slice(), self.config())
+    }
+
+    fn recv_response(&mut self) -> Result<Response, ProtocolError> {
+        read_frame_with(&mut self.recv_frames()?.as_slice(), self.config())
+    }
+
+    /// Sends any message: a `Request`, a `Response` or an envelope.
+    fn write_frame<T: Serialize>(&mut self, message: &T) -> Result<(), ProtocolError>
+    where
+        Self: Sized,
+    {
+        self.send_frames(&encode_frames(message, self.frame_options())?)
+    }
+
+    /// Blocks until a complete message has arrived and decodes it as `T`.
+    fn read_frame<T: DeserializeOwned>(&mut self) -> Result<T, ProtocolError>
+    where
+        Self: Sized,
+    {
+        read_frame_with(&mut self.recv_frames()?.as_slice(), self.config())
+    }
+}
+
+/// Frames messages over a blocking byte stream. Reads are not buffered, so
+/// pass a buffered stream if the underlying one makes small reads costly.
+#[derive(Debug)]
+pub struct StreamTransport<S> {
+    stream: S,
+    options: FrameOptions,
+    config: ProtocolConfig,
+}
+
+impl<S: Read + Write> StreamTransport<S> {
+    /// Uses the limits of `ProtocolConfig::default()`.
+    pub fn new(stream: S) -> Self {
+        StreamTransport::from_config(stream, &ProtocolConfig::default())
+    }
+
+    pub fn from_config(stream: S, config: &ProtocolConfig) -> Self {
+        StreamTransport { stream, options: FrameOptions::from_config(config), config: config.clone() }
+    }
+
+    /// Serializes, compresses and checksums outgoing frames as `options` say.
+    /// Incoming frames are decoded according to their own flags.
+    pub fn with_options(mut self, options: FrameOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read + Write> Transport for StreamTransport<S> {
+    fn send_frames(&mut self, frames: &[u8]) -> Result<(), ProtocolError> {
+        self.stream.write_all(frames)?;
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    fn recv_frames(&mut self) -> Result<Vec<u8>, ProtocolError> {
+        read_message_frames(&mut self.stream, &self.config)
+    }
+
+    fn frame_options(&self) -> FrameOptions {
+        self.options
+    }
+
+    fn config(&self) -> &ProtocolConfig {
+        &self.config
+    }
+}
+
+#[cfg(feature = "tcp")]
+pub type TcpTransport = StreamTransport<std::net::TcpStream>;
+
+#[cfg(feature = "tcp")]
+impl StreamTransport<std::net::TcpStream> {
+    /// Connects to a server and disables Nagle's algorithm, since every
+    /// message is written in one call.
+    pub fn connect(addr: impl std::net::ToSocketAddrs) -> Result<Self, ProtocolError> {
+        let stream = std::net::TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(StreamTransport::new(stream))
+    }
+}
+
+#[cfg(all(unix, feature = "unix"))]
+pub type UnixTransport = StreamTransport<std::os::unix::net::UnixStream>;
+
+#[cfg(all(unix, feature = "unix"))]
+impl StreamTransport<std::os::unix::net::UnixStream> {
+    /// Connects to a server listening on the socket at `path`.
+    pub fn connect(path: impl AsRef<std::path::Path>) -> Result<Self, ProtocolError> {
+        Ok(StreamTransport::new(std::os::unix::net::UnixStream::connect(path)?))
+    }
+}
+
+/// One end of an in-process connection, for tests and for embedding a server
+/// in the same process as its client. Messages are framed exactly as on a
+/// socket, so limits and options behave the same.
+#[cfg(feature = "memory")]
+#[derive(Debug)]
+pub struct MemoryTransport {
+    sender: std::sync::mpsc::Sender<Vec<u8>>,
+    receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+    options: FrameOptions,
+    config: ProtocolConfig,
+}
+
+#[cfg(feature = "memory")]
+impl MemoryTransport {
+    /// Creates two connected ends with the limits of
+    /// `ProtocolConfig::default()`. Once either end is dropped, reads on the
+    /// other fail with `UnexpectedEof` and writes with `BrokenPipe`.
+    pub fn pair() -> (MemoryTransport, MemoryTransport) {
+        let (a_sender, b_receiver) = std::sync::mpsc::channel();
+        let (b_sender, a_receiver) = std::sync::mpsc::channel();
+        let config = ProtocolConfig::default();
+        let end = |sender, receiver| MemoryTransport {
+            sender,
+            receiver,
+            options: FrameOptions::from_config(&config),
+            config: config.clone(),
+        };
+        (end(a_sender, a_receiver), end(b_sender, b_receiver))
+    }
+
+    /// Serializes, compresses and checksums outgoing frames as `options` say.
+    pub fn with_options(mut self, options: FrameOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+#[cfg(feature = "memory")]
+impl Transport for MemoryTransport {
+    fn send_frames(&mut self, frames: &[u8]) -> Result<(), ProtocolError> {
+        self.sender.send(frames.to_vec()).map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+        Ok(())
+    }
+
+    fn recv_frames(&mut self) -> Result<Vec<u8>, ProtocolError> {
+        Ok(self.receiver.recv().map_err(|_| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?)
+    }
+
+    fn frame_options(&self) -> FrameOptions {
+        self.options
+    }
+
+    fn config(&self) -> &ProtocolConfig {
+        &self.config
+    }
+}
@@ -4,37 +4,298 @@
 // requests and responses. Keeping them separate ensures they can be reused
 // without creating circular dependencies.
 
+use crate::error::ProtocolError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 
 /// A type alias for a single record, represented as a map of field names to JSON values.
 pub type Record = HashMap<String, Value>;
 
+/// The reserved field through which the server reports when a record with a
+/// TTL expires, in microseconds since the Unix epoch. It is added to records
+/// returned by reads and is ignored in written data.
+pub const EXPIRES_AT_FIELD: &str = "_expires_at";
+
 /// Represents a set of records returned from a query.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct RecordSet {
     pub records: Vec<Record>,
+    /// Set when the server stopped adding records because the query's
+    /// `max_response_bytes` budget was reached.
+    #[serde(default)]
+    pub truncated: bool,
+    /// An opaque token for fetching the records left out of a truncated result.
+    pub continuation: Option<String>,
+}
+
+/// A record tagged with the collection it was read from, for queries that
+/// span several collections.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaggedRecord {
+    pub collection: String,
+    pub record: Record,
 }
 
 /// Defines a filter for querying records (the "WHERE" clause).
+///
+/// Every `field` is a `FieldPath`, so nested values can be matched with
+/// paths like `"address.city"`.
+///
+/// The range filters (`GreaterThan`, `LessThan`, `GreaterThanOrEqual`,
+/// `LessThanOrEqual` and `Between`) compare with `compare_values` and only
+/// match field values of the same `ValueType` as their bound, so a numeric
+/// bound never matches a string. Bounds used to be `f64`; JSON numbers still
+/// decode unchanged, and Rust callers can convert with `Value::from(f64)`.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Filter {
-    Equals { field: String, value: Value },
-    NotEquals { field: String, value: Value },
-    GreaterThan { field: String, value: f64 },
-    LessThan { field: String, value: f64 },
-    In { field: String, values: Vec<Value> },
+    Equals {
+        field: FieldPath,
+        value: Value,
+        #[serde(default)]
+        options: StringCompareOptions,
+    },
+    NotEquals { field: FieldPath, value: Value },
+    GreaterThan { field: FieldPath, value: Value },
+    LessThan { field: FieldPath, value: Value },
+    In {
+        field: FieldPath,
+        values: Vec<Value>,
+        #[serde(default)]
+        options: StringCompareOptions,
+    },
     And(Vec<Filter>),
     Or(Vec<Filter>),
+    /// A named placeholder for a sub-filter, filled in with `bind_params`
+    /// before the query runs.
+    Param(String),
+    /// Matches string fields against a regular expression.
+    Matches {
+        field: FieldPath,
+        pattern: String,
+        #[serde(default)]
+        options: StringCompareOptions,
+    },
+    StartsWith {
+        field: FieldPath,
+        prefix: String,
+        #[serde(default)]
+        options: StringCompareOptions,
+    },
+    EndsWith {
+        field: FieldPath,
+        suffix: String,
+        #[serde(default)]
+        options: StringCompareOptions,
+    },
+    /// Matches records where the field is present and not null.
+    Exists { field: FieldPath },
+    /// Matches records where the field is absent or null.
+    NotExists { field: FieldPath },
+    /// Matches values between `low` and `high`; `inclusive` decides whether
+    /// the bounds themselves match.
+    Between { field: FieldPath, low: Value, high: Value, inclusive: bool },
+    GreaterThanOrEqual { field: FieldPath, value: Value },
+    LessThanOrEqual { field: FieldPath, value: Value },
+    /// Matches records the inner filter does not match.
+    Not(Box<Filter>),
+    /// Matches array fields that contain `value` as an element.
+    Contains { field: FieldPath, value: Value },
+    /// Matches array fields that contain every one of `values`.
+    ContainsAll { field: FieldPath, values: Vec<Value> },
+    /// Tokenized full-text match against a text field.
+    TextSearch { field: FieldPath, query: String, options: TextSearchOptions },
+    /// Matches points (stored as `GeoPoint`-shaped objects) inside a region.
+    GeoWithin { field: FieldPath, region: GeoRegion },
+    /// Matches points within `max_distance_meters` of `point`, measured along
+    /// the Earth's surface.
+    GeoNear { field: FieldPath, point: GeoPoint, max_distance_meters: f64 },
+    /// Matches records whose field holds a value of exactly this JSON type.
+    /// A missing field matches nothing, not even `ValueType::Null`.
+    TypeOf { field: FieldPath, value_type: ValueType },
+}
+
+/// A coordinate in decimal degrees (WGS 84).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// An area on the map for `Filter::GeoWithin`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum GeoRegion {
+    /// The box spanned by its south-west and north-east corners.
+    BoundingBox { south_west: GeoPoint, north_east: GeoPoint },
+    /// A closed polygon; the last vertex connects back to the first.
+    Polygon(Vec<GeoPoint>),
+}
+
+/// How `Equals`, `In` and the pattern filters compare strings. The default
+/// compares them exactly; non-string values are unaffected.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StringCompareOptions {
+    pub case_insensitive: bool,
+    /// Compares strings after Unicode NFC normalization.
+    pub normalize_unicode: bool,
+    /// Ignores leading and trailing whitespace.
+    pub trim: bool,
+}
+
+/// Tuning for `Filter::TextSearch`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TextSearchOptions {
+    /// Requires every query term to match instead of any one of them.
+    pub match_all_terms: bool,
+    /// Also matches words that merely start with a query term.
+    pub prefix: bool,
+    /// Drops matches whose relevance score is below this threshold.
+    pub min_score: Option<f64>,
+}
+
+/// A record paired with its score. Scored results are ordered best match
+/// first: highest relevance for text search, nearest for vector search.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoredRecord {
+    pub score: f64,
+    pub record: Record,
+}
+
+/// The path of a field within a record. Nested objects are addressed with
+/// dots and array elements with brackets, e.g. `"address.city"` or
+/// `"items[0].sku"`. Keys containing `.`, `[` or `]` cannot be addressed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldPath(pub String);
+
+/// One step of a parsed `FieldPath`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl FieldPath {
+    /// Parses `path`, rejecting malformed paths such as `"a..b"` or `"items[x]"`.
+    pub fn parse(path: &str) -> Result<FieldPath, ProtocolError> {
+        let path = FieldPath(path.to_string());
+        path.segments()?;
+        Ok(path)
+    }
+
+    /// Splits the path into its keys and array indices.
+    pub fn segments(&self) -> Result<Vec<PathSegment>, ProtocolError> {
+        let invalid = || ProtocolError::InvalidFieldPath(self.0.clone());
+        let mut segments = Vec::new();
+        let mut rest = self.0.as_str();
+        let mut expect_key = true;
+
+        while !rest.is_empty() || expect_key {
+            if expect_key {
+                let end = rest.find(['.', '[', ']']).unwrap_or(rest.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                segments.push(PathSegment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+                expect_key = false;
+            } else if let Some(after) = rest.strip_prefix('.') {
+                rest = after;
+                expect_key = true;
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let close = after.find(']').ok_or_else(invalid)?;
+                let digits = &after[..close];
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                segments.push(PathSegment::Index(digits.parse().map_err(|_| invalid())?));
+                rest = &after[close + 1..];
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Looks the path up in `record`, returning `None` if any step is missing
+    /// or the path is malformed.
+    pub fn resolve<'a>(&self, record: &'a Record) -> Option<&'a Value> {
+        let segments = self.segments().ok()?;
+        let (first, rest) = segments.split_first()?;
+        let PathSegment::Key(key) = first else { return None };
+        rest.iter().try_fold(record.get(key)?, |value, segment| match segment {
+            PathSegment::Key(key) => value.get(key.as_str()),
+            PathSegment::Index(index) => value.get(*index),
+        })
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for FieldPath {
+    fn from(path: &str) -> Self {
+        FieldPath(path.to_string())
+    }
+}
+
+impl From<String> for FieldPath {
+    fn from(path: String) -> Self {
+        FieldPath(path)
+    }
 }
 
 /// Defines query modifiers like sorting, limiting, and pagination.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct QueryOptions {
-    pub sort_by: Option<(String, Direction)>,
+    /// Sorts by a field, which may be nested.
+    pub sort_by: Option<(FieldPath, Direction)>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    /// Sorts by a computed key. Takes precedence over `sort_by` when both are set.
+    pub sort_expr: Option<(SortExpr, Direction)>,
+    /// Keeps only the first record, in sort order, for each distinct value of this field.
+    pub distinct_on: Option<FieldPath>,
+    /// Caps the encoded size of the returned records. Once the budget is hit
+    /// the server stops appending records and marks the result as truncated.
+    pub max_response_bytes: Option<ByteSize>,
+    /// Asks for the results one page at a time: the query is answered with a
+    /// `RecordSetPage` of at most this many records and a `Cursor` for the rest.
+    pub page_size: Option<usize>,
+    /// Returns only the first `k` records in sort order. Unlike `limit`, this
+    /// tells the server up front to keep just `k` candidates in a heap while
+    /// scanning rather than sorting every match.
+    pub top_k: Option<usize>,
+    /// Only returns records whose `RecordAcl` grants the caller at least
+    /// this access. Records without an ACL are unaffected.
+    pub visible_as: Option<AclAccess>,
+    /// Asks the server to send the results as a series of `RecordSetChunk`
+    /// responses followed by a `RecordSetEnd`, starting before the scan has
+    /// finished, instead of buffering them into one `RecordSet`.
+    #[serde(default)]
+    pub stream: bool,
+    /// Throttles the scan behind this query and any cursor it returns.
+    pub throttle: Option<ScanThrottle>,
+}
+
+/// A computed sort key, for orderings that a single field cannot express
+/// (e.g. `price * quantity`). Fields that are missing or not numeric evaluate
+/// to null and sort last.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SortExpr {
+    Field(FieldPath),
+    Constant(f64),
+    Add(Box<SortExpr>, Box<SortExpr>),
+    Subtract(Box<SortExpr>, Box<SortExpr>),
+    Multiply(Box<SortExpr>, Box<SortExpr>),
+    Divide(Box<SortExpr>, Box<SortExpr>),
+    /// The relevance score of the text search the query is part of.
+    Relevance,
 }
 
 /// Enum for sorting direction.
@@ -55,10 +316,842 @@ pub struct DbStats {
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct BatchRequest {
     pub requests: HashMap<String, (String, String, String)>, // Key -> (DB Name, Collection, Record ID)
+    /// Optional per-key projections. Keys without an entry return whole records.
+    #[serde(default)]
+    pub projections: HashMap<String, Projection>,
+}
+
+/// Selects which fields of a record are returned, and under what names.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Projection {
+    pub fields: Vec<ProjectionEntry>,
+}
+
+/// One field in a `Projection`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProjectionEntry {
+    /// Returns the field under its own name.
+    Field(FieldPath),
+    /// Returns the field `from` renamed to `to`.
+    Alias { from: FieldPath, to: String },
+    /// Returns a field named `name` computed from `expr`.
+    Computed { name: String, expr: Expr },
+}
+
+/// An expression for deriving a value from a record, e.g. `price * qty`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Expr {
+    Field(FieldPath),
+    Literal(Value),
+    Add(Box<Expr>, Box<Expr>),
+    Subtract(Box<Expr>, Box<Expr>),
+    Multiply(Box<Expr>, Box<Expr>),
+    Divide(Box<Expr>, Box<Expr>),
+    /// Joins the string forms of each part.
+    Concat(Vec<Expr>),
+    /// The first part that is present and not null; a trailing `Literal`
+    /// acts as a default.
+    Coalesce(Vec<Expr>),
+}
+
+impl From<&str> for ProjectionEntry {
+    fn from(field: &str) -> Self {
+        ProjectionEntry::Field(field.into())
+    }
 }
 
 /// The response from a batch read operation.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct BatchResponse {
     pub results: HashMap<String, Option<Record>>,
-}
\ No newline at end of file
+}
+
+/// The JSON type of a value, as seen when inspecting schemaless records.
+/// Types are declared in the cross-type order used by `compare_values`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValueType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl ValueType {
+    /// Returns the type of `value`.
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => ValueType::Null,
+            Value::Bool(_) => ValueType::Bool,
+            Value::Number(_) => ValueType::Number,
+            Value::String(_) => ValueType::String,
+            Value::Array(_) => ValueType::Array,
+            Value::Object(_) => ValueType::Object,
+        }
+    }
+}
+
+/// A field observed while sampling a collection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldInfo {
+    pub name: String,
+    /// Every type the field was seen with in the sample.
+    pub types: Vec<ValueType>,
+    /// The fraction of sampled records containing the field, from 0.0 to 1.0.
+    pub occurrence_rate: f64,
+}
+
+/// The inferred shape of a collection, built from a sample of its records.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CollectionSchema {
+    pub sampled_records: u64,
+    pub fields: Vec<FieldInfo>,
+}
+
+/// Identifies a long-running server-side operation, such as an index build,
+/// compaction, import or backup.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(pub u64);
+
+/// The lifecycle state of a job.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Queued,
+    /// `progress` runs from 0.0 to 1.0 when the job can estimate it.
+    Running { progress: Option<f32> },
+    Completed,
+    Failed(String),
+    Cancelled,
+}
+
+/// The kind of index to build on a field.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub enum IndexKind {
+    /// An ordered index for equality and range lookups.
+    #[default]
+    BTree,
+    /// An approximate nearest-neighbour index over fixed-length float vectors.
+    Vector { dimensions: usize, metric: DistanceMetric },
+    /// An inverted index over tokenized text, for `TextSearch` filters.
+    Text,
+    /// A spatial index over `GeoPoint` values, for `GeoWithin` and `GeoNear` filters.
+    Geo,
+}
+
+/// How a vector index measures the distance between two vectors.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    Cosine,
+    Euclidean,
+    DotProduct,
+}
+
+/// A machine-readable reason for a failed operation, so clients can decide
+/// whether to retry without parsing error messages.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A record with the same id already exists.
+    DuplicateKey,
+    /// A unique index or other constraint rejected the data.
+    ConstraintViolation,
+    NotFound,
+    /// The data was malformed or failed validation.
+    InvalidData,
+    Internal,
+    /// The record was not at the write's `expected_version`.
+    VersionConflict,
+    /// The transaction outlived its timeout and was rolled back.
+    TransactionExpired,
+}
+
+/// One item of a bulk or batch write that failed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WriteFailure {
+    /// The position of the item in the original write request.
+    pub index: usize,
+    pub code: ErrorCode,
+    pub message: String,
+    /// The id of the existing record the item collided with, for
+    /// `DuplicateKey` and `ConstraintViolation` failures.
+    pub conflicting_record_id: Option<String>,
+}
+
+/// Details of a bulk or batch write that partially failed. Items not listed
+/// in `failures` were written.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct BulkWriteError {
+    pub succeeded: usize,
+    pub failures: Vec<WriteFailure>,
+}
+
+/// An opaque server-issued handle to the rest of a query's results. Clients
+/// pass it back unchanged to `FetchMore` or `CloseCursor`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cursor(pub String);
+
+/// Query policy applied by the server to requests on a collection that leave
+/// the corresponding `QueryOptions` unset.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CollectionDefaults {
+    pub default_limit: Option<usize>,
+    /// Sorts by a field, which may be nested.
+    pub default_sort: Option<(FieldPath, Direction)>,
+    /// The most records a single query may scan before the server aborts it.
+    pub max_scan: Option<u64>,
+}
+
+/// How the server intends to execute a query, as returned by `ExplainQuery`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    /// The field of the index the planner chose, if any.
+    pub index: Option<String>,
+    pub scan_type: ScanType,
+    /// The planner's estimate of how many records the query will return.
+    pub estimated_rows: u64,
+    /// The execution steps, in the order records flow through them.
+    pub stages: Vec<PlanStage>,
+}
+
+/// How `ExplainQuery` returns the plan.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExplainFormat {
+    /// As a `QueryPlan`.
+    #[default]
+    Structured,
+    /// As the server's own JSON document, which may hold more detail than
+    /// `QueryPlan`. For tools such as plan visualizers that should not have
+    /// to track this crate's plan types.
+    Json,
+}
+
+/// How a query plan reads records from the collection.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanType {
+    /// Every record is read and tested against the filter.
+    CollectionScan,
+    /// A range of an index is walked.
+    IndexScan,
+    /// Individual keys are looked up in an index.
+    IndexLookup,
+}
+
+/// One step of a query plan, e.g. a filter, sort or limit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlanStage {
+    pub name: String,
+    /// A human-readable description, e.g. the filter a stage applies.
+    pub detail: Option<String>,
+    pub estimated_rows: u64,
+}
+
+/// One output value of an aggregation, computed per group.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Aggregation {
+    /// The key the computed value is reported under in `AggregateResult::values`.
+    pub name: String,
+    pub accumulator: Accumulator,
+}
+
+/// How an aggregation folds the records of a group into one value. Fields may
+/// be nested; records where the field is missing or not a number are skipped
+/// by the numeric accumulators.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Accumulator {
+    /// The number of records in the group.
+    Count,
+    Sum(FieldPath),
+    Avg(FieldPath),
+    /// The smallest value, compared with `compare_values`.
+    Min(FieldPath),
+    /// The largest value, compared with `compare_values`.
+    Max(FieldPath),
+    /// The value below which `p` percent of the group's values fall, with
+    /// `p` in `0.0..=100.0`. Servers may return an approximation on large
+    /// groups.
+    Percentile { field: FieldPath, p: f64 },
+    /// The sample standard deviation.
+    StdDev(FieldPath),
+    /// The 50th percentile.
+    Median(FieldPath),
+}
+
+/// The aggregated values for one group.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct AggregateResult {
+    /// The group's value for each `group_by` field.
+    pub group: Record,
+    /// Each aggregation's value, keyed by `Aggregation::name`.
+    pub values: Record,
+}
+
+/// A multi-stage analytical query. Each stage consumes the rows produced by
+/// the one before it, starting from the records of the target collection.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct AggregationPipeline {
+    pub stages: Vec<PipelineStage>,
+}
+
+/// One step of an `AggregationPipeline`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PipelineStage {
+    /// Keeps only the rows matching the filter.
+    Match(Filter),
+    /// Reshapes each row, keeping, renaming or computing fields.
+    Project(Projection),
+    /// Replaces the rows with one row per distinct combination of the `by`
+    /// fields, holding those fields plus one field per aggregation.
+    Group { by: Vec<FieldPath>, aggregations: Vec<Aggregation> },
+    /// Orders the rows by each key in turn.
+    Sort(Vec<(FieldPath, Direction)>),
+    Limit(usize),
+    /// Joins each row with the records of `from_collection`, in the same
+    /// database, whose `foreign_field` equals the row's `local_field`. The
+    /// matches are stored as an array in `as_field`, which is empty when
+    /// nothing matches.
+    Lookup { from_collection: String, local_field: FieldPath, foreign_field: FieldPath, as_field: String },
+    /// Replaces each row with one row per element of the array at the path,
+    /// holding that element in place of the array. Rows where the path is
+    /// missing or holds an empty array are dropped; a non-array value is
+    /// treated as a one-element array.
+    Unwind(FieldPath),
+}
+
+/// The field changes of a partial update, applied in order and atomically.
+pub type UpdateOps = Vec<UpdateOp>;
+
+/// One change to a record. Fields may be nested, and missing intermediate
+/// objects are created.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum UpdateOp {
+    Set { field: FieldPath, value: Value },
+    /// Removes the field; a missing field is left as is.
+    Unset { field: FieldPath },
+    /// Adds a number to a numeric field, treating a missing field as 0.
+    Increment { field: FieldPath, by: Value },
+    /// Multiplies a numeric field, treating a missing field as 0.
+    Multiply { field: FieldPath, by: Value },
+    /// Appends to an array field, creating the array if the field is missing.
+    Push { field: FieldPath, value: Value },
+    /// Removes every element equal to `value` from an array field.
+    Pull { field: FieldPath, value: Value },
+}
+
+/// Identifies a request the server is currently executing.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OperationId(pub u64);
+
+/// A request in progress on the server, as listed by `ListOperations`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OperationInfo {
+    pub op_id: OperationId,
+    /// The request's variant name, e.g. `"FindRecords"`.
+    pub request_type: String,
+    /// How long the operation has been running, in microseconds.
+    pub duration_micros: u64,
+    /// The `db.collection` the operation works on, if any.
+    pub target: Option<String>,
+}
+
+/// Identifies a client connection to the server.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionId(pub u64);
+
+/// A client connection, as listed by `ListConnections`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConnectionInfo {
+    pub conn_id: ConnectionId,
+    /// The client's address, e.g. `"10.0.0.7:52114"`.
+    pub peer: String,
+    /// The authenticated user, if any.
+    pub user: Option<String>,
+    /// Time since the connection last sent a request, in microseconds.
+    pub idle_micros: u64,
+    /// Requests from this connection the server is still executing.
+    pub in_flight_ops: u32,
+}
+
+/// Which image of a modified record a write returns.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnDocument {
+    /// The record as it was before the write.
+    Before,
+    /// The record as it is after the write.
+    After,
+}
+
+/// One page of a paginated list response, used for databases, collections
+/// and indexes so clients page through them the same way.
+///
+/// Query results are not listed with `Page`: they page through a server-held
+/// `Cursor` (`RecordSetPage`, `FetchMore`, `CloseCursor`) so the server can
+/// keep a consistent scan open, which a stateless page token cannot do.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page_info: PageInfo,
+}
+
+/// Where a `Page` sits in the full list.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct PageInfo {
+    /// Passed back in the request to fetch the next page; `None` on the last page.
+    pub next_page_token: Option<String>,
+    /// The size of the full list, when the server can compute it cheaply.
+    pub total_count: Option<u64>,
+}
+
+/// Narrows and pages a listing of names, such as databases or collections.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ListOptions {
+    /// Only lists names starting with this prefix.
+    pub name_prefix: Option<String>,
+    /// The most names to return in one page.
+    pub limit: Option<usize>,
+    /// The `next_page_token` of the previous page; `None` starts from the beginning.
+    pub page_token: Option<String>,
+}
+
+/// An index on a collection, as listed by `ListIndexes`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexInfo {
+    /// The indexed fields, in key order.
+    pub fields: Vec<FieldPath>,
+    pub unique: bool,
+    pub kind: IndexKind,
+    /// The index's on-disk size.
+    pub size: ByteSize,
+    pub state: IndexState,
+}
+
+/// Whether an index can serve queries yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum IndexState {
+    /// `progress` runs from 0.0 to 1.0 when the server can estimate it.
+    Building { progress: Option<f32> },
+    Ready,
+    Failed(String),
+}
+
+/// A request object for a batch of write operations.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct BatchWriteRequest {
+    /// `None` uses the database selected for the connection with `UseDatabase`.
+    pub db_name: Option<String>,
+    pub operations: Vec<WriteOperation>,
+    /// When set, operations run in order and the batch stops at the first
+    /// failure. Otherwise they may run in any order and every one is attempted.
+    pub ordered: bool,
+}
+
+/// One write in a `BatchWriteRequest`, with the same semantics as the
+/// corresponding single-record request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum WriteOperation {
+    Create { collection: String, record_id: String, data: Record, ttl: Option<DurationMs> },
+    Update { collection: String, record_id: String, data: Record, expected_version: Option<u64> },
+    Upsert {
+        collection: String,
+        record_id: String,
+        data: Record,
+        expected_version: Option<u64>,
+        ttl: Option<DurationMs>,
+    },
+    Delete { collection: String, record_id: String, cascade: bool, expected_version: Option<u64> },
+}
+
+/// The response from a batch write operation: one result per operation, in
+/// request order.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct BatchWriteResponse {
+    pub results: Vec<WriteResult>,
+}
+
+/// The outcome of one operation in a batch write.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum WriteResult {
+    /// `version` is the record's new version, when the server tracks versions.
+    Applied { version: Option<u64> },
+    Failed(WriteFailure),
+    /// Not attempted because an earlier operation of an `ordered` batch failed.
+    Skipped,
+}
+
+/// Who may access a record, stored on the record itself under `RecordAcl::FIELD`.
+/// Records without one are governed by collection-level permissions only.
+/// Owners may also read and write, and writers may also read.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct RecordAcl {
+    #[serde(default)]
+    pub owners: Vec<String>,
+    #[serde(default)]
+    pub readers: Vec<String>,
+    #[serde(default)]
+    pub writers: Vec<String>,
+}
+
+impl RecordAcl {
+    /// The reserved record field holding the ACL.
+    pub const FIELD: &'static str = "_acl";
+
+    /// Reads the ACL from `record`, returning `None` if it has none.
+    pub fn from_record(record: &Record) -> Result<Option<RecordAcl>, ProtocolError> {
+        match record.get(Self::FIELD) {
+            Some(value) => Ok(Some(RecordAcl::deserialize(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the ACL on `record`, replacing any existing one.
+    pub fn apply_to(&self, record: &mut Record) -> Result<(), ProtocolError> {
+        record.insert(Self::FIELD.to_string(), serde_json::to_value(self)?);
+        Ok(())
+    }
+
+    /// Whether `principal` is granted `access` by this ACL.
+    pub fn allows(&self, principal: &str, access: AclAccess) -> bool {
+        let listed = |principals: &[String]| principals.iter().any(|p| p == principal);
+        listed(&self.owners)
+            || match access {
+                AclAccess::Read => listed(&self.readers) || listed(&self.writers),
+                AclAccess::Write => listed(&self.writers),
+                AclAccess::Own => false,
+            }
+    }
+}
+
+/// A level of access to a record, from least to most privileged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclAccess {
+    Read,
+    Write,
+    Own,
+}
+
+/// Settings fixed when a collection is created.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CollectionOptions {
+    #[serde(default)]
+    pub lifetime: CollectionLifetime,
+    /// Stores the collection as time-series data, enabling `DownsampleQuery`.
+    pub time_series: Option<TimeSeriesOptions>,
+}
+
+/// How long a collection and its records are kept.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionLifetime {
+    /// Persisted to durable storage like any other collection.
+    #[default]
+    Durable,
+    /// Held in memory only and dropped when the server restarts.
+    UntilRestart,
+    /// Held in memory only and dropped when the creating connection closes.
+    /// Other connections cannot see it.
+    UntilDisconnect,
+}
+
+/// When a materialized view recomputes its contents.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshPolicy {
+    /// Only when asked with `RefreshView`.
+    Manual,
+    /// Automatically, `every` after the previous refresh finished.
+    Interval { every: DurationMs },
+}
+
+/// Freshness and size of a materialized view.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ViewStats {
+    pub record_count: u64,
+    /// When the last refresh finished, in microseconds since the Unix epoch.
+    /// `None` until the first refresh completes.
+    pub last_refreshed_micros: Option<u64>,
+    /// How long ago the last refresh finished, in microseconds.
+    pub staleness_micros: Option<u64>,
+    pub refreshing: bool,
+}
+
+/// Layout of a time-series collection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimeSeriesOptions {
+    /// The field holding each record's timestamp.
+    pub time_field: String,
+    /// The expected spacing of points, which the server uses to size its
+    /// storage buckets.
+    pub granularity: Granularity,
+}
+
+/// The typical interval between points of a time series.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Seconds,
+    Minutes,
+    Hours,
+}
+
+/// A half-open span of time, `[start_micros, end_micros)`, in microseconds
+/// since the Unix epoch.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start_micros: u64,
+    pub end_micros: u64,
+}
+
+/// The aggregated values for one bucket of a `DownsampleQuery`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TimeBucket {
+    /// The start of the bucket, in microseconds since the Unix epoch.
+    pub start_micros: u64,
+    /// Each aggregation's value, keyed by `Aggregation::name`.
+    pub values: Record,
+}
+
+/// Identifies an open transaction, as returned by `BeginTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransactionId(pub u64);
+
+/// Names a transaction prepared by `PrepareTransaction` until it is committed
+/// or aborted. Unlike a `TransactionId` it outlives the connection and
+/// survives server restarts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreparedToken(pub String);
+
+/// How a transaction opened by `BeginTransaction` behaves.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TransactionOptions {
+    /// Rejects writes in the transaction, so the server can skip conflict
+    /// tracking. Cheaper for reporting queries.
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub isolation: IsolationLevel,
+    /// Rolls the transaction back if it is still open after this long, so
+    /// an abandoned transaction does not hold its snapshot forever. The
+    /// server may cap it, or apply its own timeout when this is `None`.
+    #[serde(default)]
+    pub timeout: Option<DurationMs>,
+}
+
+/// Where a transaction is in its lifecycle, as reported by
+/// `GetTransactionStatus`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TransactionStatus {
+    pub tx_id: TransactionId,
+    pub state: TransactionState,
+    pub options: TransactionOptions,
+    /// How long ago the transaction began.
+    pub age: DurationMs,
+    /// How long the transaction may stay open before it expires, while it
+    /// is `Active`.
+    pub expires_in: Option<DurationMs>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransactionState {
+    Active,
+    /// Prepared for a two-phase commit; see `PrepareTransaction`.
+    Prepared,
+    Committed,
+    RolledBack,
+    /// Aborted by a conflict when it tried to commit.
+    Aborted,
+    /// Rolled back by the server when its timeout passed.
+    Expired,
+}
+
+/// How far a transaction is isolated from concurrent ones, weakest first.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsolationLevel {
+    /// Each read sees the data committed before that read began.
+    ReadCommitted,
+    /// Every read sees the snapshot taken when the transaction began, and
+    /// commit fails if another transaction wrote the same records.
+    #[default]
+    SnapshotIsolation,
+    /// Transactions behave as if they had run one at a time.
+    Serializable,
+}
+
+/// A protocol version. Peers with the same `major` version can talk to each
+/// other; a higher `minor` only adds messages the other peer may not know.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// Whether a peer speaking `self` can talk to a peer speaking `other`.
+    pub fn is_compatible_with(&self, other: &ProtocolVersion) -> bool {
+        self.major == other.major
+    }
+}
+
+/// Where a committed write landed in the server's journal. Receipts from the
+/// same server are ordered by `sequence_number`, so clients can use them as
+/// causal tokens, change-stream resume points or audit references.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteReceipt {
+    pub sequence_number: u64,
+    /// When the write committed, in microseconds since the Unix epoch.
+    pub commit_timestamp_micros: u64,
+}
+
+/// What a `DryRun` request would have done.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct DryRunReport {
+    /// Records that would be created, changed or deleted. For DDL, the
+    /// records in the databases or collections that would be dropped.
+    pub records_affected: u64,
+    /// The names of the unique indexes, schemas and other constraints the
+    /// writes were checked against.
+    pub constraints_checked: Vec<String>,
+    /// Conditions that would not stop the request but may surprise the
+    /// caller, such as a drop that would invalidate a materialized view.
+    pub warnings: Vec<String>,
+}
+
+/// A one-time token the server issues to confirm a destructive request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfirmationToken(pub String);
+
+/// A length of time in whole milliseconds. Serialized as a bare integer, so it
+/// can replace a `u64` millisecond field without changing the wire format.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct DurationMs(pub u64);
+
+impl DurationMs {
+    pub const fn from_millis(millis: u64) -> Self {
+        DurationMs(millis)
+    }
+
+    pub const fn from_secs(secs: u64) -> Self {
+        DurationMs(secs.saturating_mul(1_000))
+    }
+
+    pub const fn as_millis(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<Duration> for DurationMs {
+    /// Truncates to whole milliseconds, saturating at `u64::MAX`.
+    fn from(duration: Duration) -> Self {
+        DurationMs(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+    }
+}
+
+impl From<DurationMs> for Duration {
+    fn from(duration: DurationMs) -> Self {
+        Duration::from_millis(duration.0)
+    }
+}
+
+/// An amount of data in bytes. Serialized as a bare integer, so it can replace
+/// a byte-count field without changing the wire format.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub const fn from_bytes(bytes: u64) -> Self {
+        ByteSize(bytes)
+    }
+
+    pub const fn from_kib(kib: u64) -> Self {
+        ByteSize(kib.saturating_mul(1 << 10))
+    }
+
+    pub const fn from_mib(mib: u64) -> Self {
+        ByteSize(mib.saturating_mul(1 << 20))
+    }
+
+    pub const fn as_bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Formats with the largest binary unit that keeps the value at least 1,
+    /// e.g. `512 B`, `1.5 KiB` or `16.0 MiB`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+        if self.0 < 1 << 10 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Identifies one stream of `StreamItem` responses. Assigned by the server
+/// and unique among the streams open on a connection.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamId(pub u64);
+
+/// Limits how fast the server reads a long scan, so a backfill or export
+/// does not starve production traffic. Unset fields do not throttle.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ScanThrottle {
+    /// The most records the scan reads per second, averaged over a batch.
+    pub max_records_per_second: Option<u64>,
+    /// How long the server waits after each batch before reading the next.
+    pub pause_between_batches: Option<DurationMs>,
+}
+
+/// How thoroughly `VerifyCollection` checks a collection.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyMode {
+    /// Only computes the checksums, reading each record once.
+    #[default]
+    Checksum,
+    /// Also decodes every record and checks every index entry against the
+    /// records it points to, reporting what does not match.
+    Full,
+}
+
+/// The result of `VerifyCollection`. Servers holding the same records report
+/// the same checksums regardless of storage layout or insertion order, so a
+/// replica or restored backup can be compared with its primary.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct VerificationReport {
+    pub record_count: u64,
+    /// An order-independent checksum of every record's id and contents.
+    pub content_checksum: u64,
+    /// The checksum of each index's entries, keyed by index name.
+    pub index_checksums: HashMap<String, u64>,
+    /// Problems found by `VerifyMode::Full`; always empty for `Checksum`.
+    pub corruptions: Vec<Corruption>,
+}
+
+/// One problem found while verifying a collection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Corruption {
+    /// A record's stored bytes could not be decoded.
+    UnreadableRecord { record_id: String, detail: String },
+    /// An index entry points to a record that does not exist.
+    DanglingIndexEntry { index: String, record_id: String },
+    /// A record is missing from an index that should contain it.
+    MissingIndexEntry { index: String, record_id: String },
+}
+
+/// A request variant the server still accepts but will stop accepting, as
+/// listed in `HelloAck` so clients can warn when they connect instead of
+/// failing once the variant is gone.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Deprecation {
+    /// The deprecated variant, as returned by `Request::variant_name`.
+    pub variant: String,
+    /// The protocol version that deprecated it.
+    pub since: ProtocolVersion,
+    /// The first protocol version that no longer accepts it.
+    pub sunset: ProtocolVersion,
+    /// The variant to use instead, if there is one.
+    pub replacement: Option<String>,
+}
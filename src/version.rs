@@ -0,0 +1,136 @@
+// File: src/version.rs
+// =============================================================================
+// This file defines the protocol version this crate speaks and the optional
+// capabilities peers negotiate in `Hello`, so clients, servers and tooling can
+// work out what is safe to send to a peer without trial and error.
+//
+// Every message in this release is part of 1.0. Messages and capabilities
+// added later bump `minor`, and capabilities record the version they were
+// added in.
+
+use crate::types::ProtocolVersion;
+use crate::Request;
+
+/// The version of the protocol implemented by this crate.
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion { major: 1, minor: 0 };
+
+/// An optional feature a connection can enable by listing its `name` in
+/// `Hello::supported_features`. It is only used once the server echoes it
+/// in `HelloAck::negotiated_features`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Writes are answered with `WithReceipt`.
+    WriteReceipts,
+    /// Frames may be compressed with `Compression::Lz4`.
+    Lz4Compression,
+    /// Frames may be compressed with `Compression::Zstd`.
+    ZstdCompression,
+    /// Messages may be serialized as `WireFormat::Json`.
+    JsonFormat,
+    /// Messages may be serialized as `WireFormat::MessagePack`.
+    MessagePackFormat,
+    /// Messages may be serialized as `WireFormat::Cbor`.
+    CborFormat,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 6] = [
+        Capability::WriteReceipts,
+        Capability::Lz4Compression,
+        Capability::ZstdCompression,
+        Capability::JsonFormat,
+        Capability::MessagePackFormat,
+        Capability::CborFormat,
+    ];
+
+    /// The feature string used for this capability in the handshake.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Capability::WriteReceipts => "write_receipts",
+            Capability::Lz4Compression => "compression:lz4",
+            Capability::ZstdCompression => "compression:zstd",
+            Capability::JsonFormat => "format:json",
+            Capability::MessagePackFormat => "format:msgpack",
+            Capability::CborFormat => "format:cbor",
+        }
+    }
+
+    /// Looks a capability up by its handshake feature string.
+    pub fn from_name(name: &str) -> Option<Capability> {
+        Capability::ALL.into_iter().find(|capability| capability.name() == name)
+    }
+
+    /// The first protocol version that defines this capability.
+    pub fn since(&self) -> ProtocolVersion {
+        match self {
+            Capability::WriteReceipts
+            | Capability::Lz4Compression
+            | Capability::ZstdCompression
+            | Capability::JsonFormat
+            | Capability::MessagePackFormat
+            | Capability::CborFormat => ProtocolVersion { major: 1, minor: 0 },
+        }
+    }
+}
+
+impl ProtocolVersion {
+    /// Whether a peer speaking this version knows `capability`.
+    pub fn supports(&self, capability: Capability) -> bool {
+        let since = capability.since();
+        self.major == since.major && self.minor >= since.minor
+    }
+}
+
+impl Request {
+    /// The first protocol version that defines this request, or `None` for
+    /// `Request::Unknown`, which never goes on the wire. No request needs a
+    /// `Capability`: those only change how messages are framed and answered.
+    pub fn since(&self) -> Option<ProtocolVersion> {
+        match self {
+            Request::Unknown => None,
+            _ => Some(ProtocolVersion { major: 1, minor: 0 }),
+        }
+    }
+}
+
+/// Whether, and at which version, two peers can talk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// The peers share a major version. Only messages and capabilities that
+    /// `common`, the older of the two versions, defines are safe to send.
+    Compatible { common: ProtocolVersion },
+    /// The major versions differ, so the peers cannot talk at all.
+    Incompatible,
+}
+
+impl Compatibility {
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, Compatibility::Compatible { .. })
+    }
+
+    /// Whether both peers know `capability`. It still has to be negotiated
+    /// in `Hello` before use.
+    pub fn supports(&self, capability: Capability) -> bool {
+        match self {
+            Compatibility::Compatible { common } => common.supports(capability),
+            Compatibility::Incompatible => false,
+        }
+    }
+
+    /// Whether both peers know `request`, so it is safe to send.
+    pub fn allows(&self, request: &Request) -> bool {
+        match (self, request.since()) {
+            (Compatibility::Compatible { common }, Some(since)) => common.major == since.major && common.minor >= since.minor,
+            _ => false,
+        }
+    }
+}
+
+/// Works out what a client speaking `client` and a server speaking `server`
+/// can exchange.
+pub fn is_compatible(client: ProtocolVersion, server: ProtocolVersion) -> Compatibility {
+    if !client.is_compatible_with(&server) {
+        return Compatibility::Incompatible;
+    }
+    Compatibility::Compatible { common: client.min(server) }
+}
@@ -0,0 +1,197 @@
+// File: src/view.rs
+// =============================================================================
+// This file provides borrowed views of JSON-encoded responses, for proxies
+// and routers that inspect or relay large result sets. Decoding a `Response`
+// builds a `HashMap<String, Value>` for every record; a `ResponseRef` only
+// finds where each record starts and ends in the input, and a `RecordRef`
+// parses a field only when asked for it.
+//
+// Views need a self-describing encoding that keeps records intact, so they
+// read `WireFormat::Json`. bincode cannot carry record values at all.
+
+use crate::error::ProtocolError;
+use crate::response::Response;
+use crate::types::Record;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::value::RawValue;
+use std::fmt;
+
+/// A JSON-encoded `Response` that has only been split into its variant name
+/// and undecoded body.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseRef<'a> {
+    json: &'a str,
+    variant: &'a str,
+    /// `None` for unit variants such as `Success`.
+    body: Option<&'a RawValue>,
+}
+
+impl<'a> ResponseRef<'a> {
+    pub fn parse(json: &'a str) -> Result<Self, ProtocolError> {
+        let raw: &'a RawValue = serde_json::from_str(json)?;
+        if raw.get().starts_with('"') {
+            return Ok(ResponseRef { json, variant: serde_json::from_str(raw.get())?, body: None });
+        }
+        let (variant, body) = serde_json::from_str::<SingleEntry<'a>>(raw.get())?.0;
+        Ok(ResponseRef { json, variant, body: Some(body) })
+    }
+
+    /// The variant name, as `Response::variant_name` would return it.
+    pub fn variant_name(&self) -> &'a str {
+        self.variant
+    }
+
+    /// The variant's undecoded JSON, or `None` for a unit variant.
+    pub fn body(&self) -> Option<&'a RawValue> {
+        self.body
+    }
+
+    /// The records carried by a `Record`, `RecordSet`, `RecordSetPage` or
+    /// `RecordSetChunk`, in order. Returns `None` for any other variant.
+    pub fn records(&self) -> Result<Option<Vec<RecordRef<'a>>>, ProtocolError> {
+        let Some(body) = self.body else {
+            return Ok(None);
+        };
+        let records = match self.variant {
+            "Record" if body.get() == "null" => return Ok(Some(Vec::new())),
+            "Record" => return Ok(Some(vec![RecordRef(body)])),
+            "RecordSet" | "RecordSetPage" | "RecordSetChunk" => match field(body, "records")? {
+                Some(records) => records,
+                None => return Err(<serde_json::Error as de::Error>::missing_field("records").into()),
+            },
+            _ => return Ok(None),
+        };
+        let records: Vec<&'a RawValue> = serde_json::from_str(records.get())?;
+        Ok(Some(records.into_iter().map(RecordRef).collect()))
+    }
+
+    /// The whole response's JSON, ready to relay unchanged.
+    pub fn as_json(&self) -> &'a str {
+        self.json
+    }
+
+    /// Fully decodes the response.
+    pub fn decode(&self) -> Result<Response, ProtocolError> {
+        Ok(serde_json::from_str(self.json)?)
+    }
+}
+
+/// One record of a `ResponseRef`, still in its JSON form.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordRef<'a>(&'a RawValue);
+
+impl<'a> RecordRef<'a> {
+    /// The record's JSON, ready to relay unchanged.
+    pub fn as_json(&self) -> &'a str {
+        self.0.get()
+    }
+
+    /// The undecoded value of the top-level field `name`, if present. Scans
+    /// the record without allocating.
+    pub fn get(&self, name: &str) -> Result<Option<&'a RawValue>, ProtocolError> {
+        field(self.0, name)
+    }
+
+    /// Decodes the value of the top-level field `name` as `T`.
+    pub fn get_as<T: de::Deserialize<'a>>(&self, name: &str) -> Result<Option<T>, ProtocolError> {
+        match self.get(name)? {
+            Some(value) => Ok(Some(serde_json::from_str(value.get())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Decodes the whole record.
+    pub fn to_record(&self) -> Result<Record, ProtocolError> {
+        Ok(serde_json::from_str(self.0.get())?)
+    }
+}
+
+/// Finds the value of key `name` in the JSON object `object`.
+fn field<'a>(object: &'a RawValue, name: &str) -> Result<Option<&'a RawValue>, ProtocolError> {
+    let mut deserializer = serde_json::Deserializer::from_str(object.get());
+    Ok(FieldLookup(name).deserialize(&mut deserializer)?)
+}
+
+/// Looks up one key of a JSON object, skipping the other values unparsed.
+struct FieldLookup<'k>(&'k str);
+
+impl<'de> DeserializeSeed<'de> for FieldLookup<'_> {
+    type Value = Option<&'de RawValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldLookup<'_> {
+    type Value = Option<&'de RawValue>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = None;
+        while let Some(matches) = map.next_key_seed(KeyMatches(self.0))? {
+            if matches && found.is_none() {
+                found = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// Compares a map key with an expected name, borrowed or not, without
+/// allocating.
+struct KeyMatches<'k>(&'k str);
+
+impl<'de> DeserializeSeed<'de> for KeyMatches<'_> {
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for KeyMatches<'_> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string key")
+    }
+
+    fn visit_str<E: de::Error>(self, key: &str) -> Result<bool, E> {
+        Ok(key == self.0)
+    }
+}
+
+/// An externally tagged enum's only entry: the variant name and its body.
+struct SingleEntry<'a>((&'a str, &'a RawValue));
+
+impl<'de> de::Deserialize<'de> for SingleEntry<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(SingleEntryVisitor)
+    }
+}
+
+struct SingleEntryVisitor;
+
+impl<'de> Visitor<'de> for SingleEntryVisitor {
+    type Value = SingleEntry<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a variant name or an object with one entry")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let Some(entry) = map.next_entry()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+        if map.next_key::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(SingleEntry(entry))
+    }
+}